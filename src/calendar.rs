@@ -0,0 +1,113 @@
+use chrono::{Datelike, Month, NaiveDate, Weekday};
+use colored::Colorize;
+
+use crate::{config::Config, time::days_in_month};
+
+/// Renders a cal(1)-style grid of `month`, with every day that has at least one matching rule
+/// highlighted, followed by the messages of each of those days. `today` is shown in reverse
+/// video. Nothing is highlighted unless `color` is set.
+pub fn render_month(
+    config: &Config,
+    year: i32,
+    month: u32,
+    today: NaiveDate,
+    color: bool,
+) -> String {
+    let week_start_day = config.week_start_day.unwrap_or(Weekday::Sun);
+    let title = match Month::try_from(month as u8) {
+        Ok(name) => format!("{} {year}", name.name()),
+        Err(_) => return String::new(),
+    };
+    let mut out = format!("{title:^20}\n");
+
+    let header: Vec<String> = (0..7)
+        .scan(week_start_day, |day, _| {
+            let name = day.to_string()[..2].to_string();
+            *day = day.succ();
+            Some(name)
+        })
+        .collect();
+    out.push_str(&header.join(" "));
+    out.push('\n');
+
+    let mut listing = String::new();
+    let mut column = match NaiveDate::from_ymd_opt(year, month, 1) {
+        Some(first) => first.weekday().days_since(week_start_day),
+        None => return out,
+    };
+    out.push_str(&"   ".repeat(column as usize));
+    for day in 1..=days_in_month(year, month) {
+        let Some(date) = NaiveDate::from_ymd_opt(year, month, day) else {
+            continue;
+        };
+        let messages = crate::messages_on(config, date);
+        let number = format!("{day:>2}");
+        let mut cell = number.normal();
+        if !messages.is_empty() {
+            cell = cell.yellow().bold();
+        }
+        if date == today {
+            cell = cell.reversed();
+        }
+        match color {
+            true => out.push_str(&cell.to_string()),
+            false => out.push_str(&number),
+        }
+        for (_, message) in messages {
+            match color {
+                true => listing.push_str(&format!("{} {message}\n", number.yellow())),
+                false => listing.push_str(&format!("{number} {message}\n")),
+            }
+        }
+
+        column += 1;
+        if column == 7 {
+            column = 0;
+            out.push('\n');
+        } else if day != days_in_month(year, month) {
+            out.push(' ');
+        }
+    }
+    if column != 0 {
+        out.push('\n');
+    }
+    if !listing.is_empty() {
+        out.push('\n');
+        out.push_str(&listing);
+    }
+    out
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn render() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "dates": [
+                    { "message": "payday", "time": { "day_of": { "month": [-1] } } },
+                    { "message": "fireworks", "time": { "day_of": { "month": [4] }, "month": ["July"] } }
+                ],
+                "week_start_day": "Mon"
+            }"#,
+        )
+        .unwrap();
+        let today = NaiveDate::from_ymd_opt(2025, 7, 4).unwrap();
+        let rendered = render_month(&config, 2025, 7, today, false);
+        assert_eq!(
+            rendered,
+            "     July 2025      \n\
+             Mo Tu We Th Fr Sa Su\n   \
+             \x201  2  3  4  5  6\n \
+             7  8  9 10 11 12 13\n\
+             14 15 16 17 18 19 20\n\
+             21 22 23 24 25 26 27\n\
+             28 29 30 31\n\
+             \n \
+             4 fireworks\n\
+             31 payday\n"
+        );
+    }
+}
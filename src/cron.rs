@@ -0,0 +1,257 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::ConfigError;
+
+/// A parsed cron expression. Accepts the standard 5 fields (`minute hour day-of-month month
+/// day-of-week`), or 6 fields with a leading `second` field, as well as the `@yearly`,
+/// `@monthly`, `@weekly`, `@daily` and `@hourly` shorthands. With 5 fields, a schedule matches for
+/// the whole minute.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(try_from = "String", into = "String")]
+pub struct CronSchedule {
+    source: String,
+    second: Field,
+    minute: Field,
+    hour: Field,
+    day_of_month: Field,
+    month: Field,
+    day_of_week: Field,
+}
+
+/// The allowed values of a single cron field, as a bitset.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Field {
+    bits: u64,
+    /// Whether the field starts with `*` or `?`.
+    any: bool,
+}
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+impl CronSchedule {
+    /// Checks if `dt` matches this schedule. Like in standard cron, if both the day-of-month and
+    /// day-of-week fields are restricted, a day matches if *either* of them matches.
+    pub fn matches(&self, dt: DateTime<FixedOffset>) -> bool {
+        self.second.contains(dt.second())
+            && self.minute.contains(dt.minute())
+            && self.hour.contains(dt.hour())
+            && self.matches_date(dt.date_naive())
+    }
+
+    /// Checks if this schedule fires during the minute that starts at `time`, on any date.
+    pub fn matches_minute(&self, time: NaiveTime) -> bool {
+        self.minute.contains(time.minute()) && self.hour.contains(time.hour())
+    }
+
+    /// Checks if this schedule fires at any time during `date`.
+    pub fn matches_date(&self, date: NaiveDate) -> bool {
+        self.month.contains(date.month()) && self.matches_day(date)
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let dom = self.day_of_month.contains(date.day());
+        let dow = self
+            .day_of_week
+            .contains(date.weekday().num_days_from_sunday());
+        match (self.day_of_month.any, self.day_of_week.any) {
+            (false, false) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+}
+
+impl Field {
+    fn contains(&self, value: u32) -> bool {
+        self.bits & (1 << value) != 0
+    }
+
+    fn parse(
+        field: &str,
+        min: u32,
+        max: u32,
+        names: &[&str],
+        name_offset: u32,
+    ) -> Result<Self, String> {
+        let mut bits = 0;
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => (
+                    range,
+                    step.parse::<u32>()
+                        .ok()
+                        .filter(|&s| s > 0)
+                        .ok_or(format!("invalid step `{step}`"))?,
+                ),
+                None => (part, 1),
+            };
+            let (start, end) = match range {
+                "*" | "?" => (min, max),
+                _ => match range.split_once('-') {
+                    Some((start, end)) => (
+                        parse_value(start, min, max, names, name_offset)?,
+                        parse_value(end, min, max, names, name_offset)?,
+                    ),
+                    // `5/15` means "every 15, starting at 5".
+                    None if step > 1 => (parse_value(range, min, max, names, name_offset)?, max),
+                    None => {
+                        let value = parse_value(range, min, max, names, name_offset)?;
+                        (value, value)
+                    }
+                },
+            };
+            if start > end {
+                return Err(format!("range `{range}` is backwards"));
+            }
+            for value in (start..=end).step_by(step as usize) {
+                bits |= 1 << value;
+            }
+        }
+        Ok(Field {
+            bits,
+            any: field.starts_with(['*', '?']),
+        })
+    }
+}
+
+fn parse_value(
+    value: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    name_offset: u32,
+) -> Result<u32, String> {
+    let parsed = value.parse::<u32>().ok().or_else(|| {
+        names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(value))
+            .map(|idx| idx as u32 + name_offset)
+    });
+    match parsed {
+        Some(v) if (min..=max).contains(&v) => Ok(v),
+        _ => Err(format!("`{value}` is not a value from {min}-{max}")),
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| ConfigError::InvalidCron(s.to_string(), reason);
+        let expanded = match s.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let (second, rest) = match fields.len() {
+            5 => ("*", &fields[..]),
+            6 => (fields[0], &fields[1..]),
+            n => return Err(invalid(format!("expected 5 or 6 fields, found {n}"))),
+        };
+        let mut day_of_week = Field::parse(rest[4], 0, 7, &WEEKDAYS, 0).map_err(invalid)?;
+        // both 0 and 7 are Sunday.
+        if day_of_week.contains(7) {
+            day_of_week.bits |= 1;
+        }
+        Ok(CronSchedule {
+            source: s.to_string(),
+            second: Field::parse(second, 0, 59, &[], 0).map_err(invalid)?,
+            minute: Field::parse(rest[0], 0, 59, &[], 0).map_err(invalid)?,
+            hour: Field::parse(rest[1], 0, 23, &[], 0).map_err(invalid)?,
+            day_of_month: Field::parse(rest[2], 1, 31, &[], 0).map_err(invalid)?,
+            month: Field::parse(rest[3], 1, 12, &MONTHS, 1).map_err(invalid)?,
+            day_of_week,
+        })
+    }
+}
+impl TryFrom<String> for CronSchedule {
+    type Error = ConfigError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+impl Display for CronSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+impl From<CronSchedule> for String {
+    fn from(value: CronSchedule) -> Self {
+        value.source
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use chrono::{Local, TimeZone};
+
+    use super::*;
+
+    fn datetime(year: i32, month: u32, day: u32, hour: u32, min: u32) -> DateTime<FixedOffset> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, min, 0)
+            .unwrap()
+            .fixed_offset()
+    }
+
+    #[test]
+    fn parse_and_match() {
+        let cron: CronSchedule = "*/15 9-17 * * MON-FRI".parse().unwrap();
+        // 2025-06-02 is a Monday.
+        assert!(cron.matches(datetime(2025, 6, 2, 9, 0)));
+        assert!(cron.matches(datetime(2025, 6, 2, 17, 45)));
+        assert!(!cron.matches(datetime(2025, 6, 2, 9, 10)));
+        assert!(!cron.matches(datetime(2025, 6, 2, 18, 0)));
+        assert!(!cron.matches(datetime(2025, 6, 1, 9, 0)));
+    }
+    #[test]
+    fn day_of_month_or_week() {
+        let cron: CronSchedule = "0 0 13 * 5".parse().unwrap();
+        // Friday the 13th, any Friday, and any 13th all match.
+        assert!(cron.matches(datetime(2025, 6, 13, 0, 0)));
+        assert!(cron.matches(datetime(2025, 6, 6, 0, 0)));
+        assert!(cron.matches(datetime(2025, 7, 13, 0, 0)));
+        assert!(!cron.matches(datetime(2025, 6, 12, 0, 0)));
+    }
+    #[test]
+    fn shorthands_and_names() {
+        let cron: CronSchedule = "@yearly".parse().unwrap();
+        assert!(cron.matches(datetime(2026, 1, 1, 0, 0).with_second(42).unwrap()));
+        assert!(cron.matches(datetime(2026, 1, 1, 0, 0)));
+        assert!(!cron.matches(datetime(2026, 1, 1, 0, 1)));
+        let cron: CronSchedule = "30 8 * dec sun,7".parse().unwrap();
+        assert!(cron.matches(datetime(2025, 12, 7, 8, 30)));
+        assert!(!cron.matches(datetime(2025, 11, 2, 8, 30)));
+        let cron: CronSchedule = "0 5/20 * * * *".parse().unwrap();
+        assert!(cron.matches(datetime(2025, 12, 7, 8, 45)));
+        assert!(!cron.matches(datetime(2025, 12, 7, 8, 0)));
+    }
+    #[test]
+    fn invalid() {
+        for expr in [
+            "* * * *",
+            "60 * * * *",
+            "* * 0 * *",
+            "* * * * MOON",
+            "*/0 * * * *",
+        ] {
+            assert!(
+                matches!(
+                    expr.parse::<CronSchedule>(),
+                    Err(ConfigError::InvalidCron(..))
+                ),
+                "{expr} should be invalid"
+            );
+        }
+    }
+}
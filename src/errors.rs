@@ -12,6 +12,16 @@ pub enum ConfigError {
     UndeterminableConfigLocation,
     #[error("max import depth reached (3)")]
     MaxRecursionDepth,
+    #[error("invalid date: {0}")]
+    InvalidDate(String),
+    #[error("invalid cron expression `{0}`: {1}")]
+    InvalidCron(String, String),
+    #[error("invalid rrule `{0}`: {1}")]
+    InvalidRrule(String, String),
+    #[error("invalid calendar: {0}")]
+    InvalidCalendar(String),
+    #[error("invalid color `{0}`, expected a color name like `red` or a hex color like `#ff8800`")]
+    InvalidColor(String),
     #[error("i/o error: {0}")]
     Io(#[from] std::io::Error),
     #[error("cannot parse: {0}")]
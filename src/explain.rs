@@ -0,0 +1,219 @@
+use std::{fmt::Display, path::PathBuf};
+
+use chrono::{DateTime, FixedOffset, Weekday};
+use colored::Colorize;
+
+use crate::config::{Config, TimeRangeMessage};
+
+/// Why a single rule did or did not match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// The index of the rule in `Config.dates`.
+    pub index: usize,
+    /// The `id` of the rule.
+    pub id: Option<String>,
+    pub label: String,
+    /// The file the rule was loaded from.
+    pub source: Option<PathBuf>,
+    pub matched: bool,
+    pub steps: Vec<Step>,
+}
+
+/// The result of evaluating one part of a rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// How deeply this step is nested, `0` being a top level field of the rule.
+    pub depth: usize,
+    pub name: &'static str,
+    pub matched: bool,
+    pub detail: Option<String>,
+}
+
+impl Step {
+    pub(crate) fn new(depth: usize, name: &'static str, matched: bool) -> Self {
+        Step {
+            depth,
+            name,
+            matched,
+            detail: None,
+        }
+    }
+    pub(crate) fn with_detail(mut self, detail: String) -> Self {
+        self.detail = Some(detail);
+        self
+    }
+}
+
+/// Explains every rule in `config` as if it was `at`. Shell conditions are run, but commands are
+/// not.
+pub fn explain(config: &Config, at: DateTime<FixedOffset>) -> Vec<Explanation> {
+    config
+        .dates
+        .iter()
+        .enumerate()
+        .map(|(index, message)| {
+            let (matched, steps) = message.explain(at, config.week_start_day);
+            Explanation {
+                index,
+                id: message.id.clone(),
+                label: message
+                    .label()
+                    .map_or(format!("dates[{index}]"), str::to_string),
+                source: message.source.path.clone(),
+                matched,
+                steps,
+            }
+        })
+        .collect()
+}
+
+impl TimeRangeMessage {
+    /// Evaluates this rule at `now` like `try_message_at` does, and returns whether it matched
+    /// along with the result of every field that is set.
+    pub fn explain(
+        &self,
+        now: DateTime<FixedOffset>,
+        week_start_day: Option<Weekday>,
+    ) -> (bool, Vec<Step>) {
+        self.evaluate_with_steps(now, week_start_day.unwrap_or(Weekday::Sun), false, false)
+    }
+}
+
+fn outcome(matched: bool) -> colored::ColoredString {
+    match matched {
+        true => "match".green(),
+        false => "no match".red(),
+    }
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("dates[{}]", self.index).bold())?;
+        if let Some(id) = &self.id {
+            write!(f, " ({id})")?;
+        }
+        if self.label != format!("dates[{}]", self.index) {
+            write!(f, " {:?}", self.label)?;
+        }
+        if let Some(source) = &self.source {
+            write!(f, " from {}", source.display())?;
+        }
+        writeln!(f, ": {}", outcome(self.matched))?;
+        if self.steps.is_empty() {
+            writeln!(f, "  {}", "no time, cron, rrule or condition set".dimmed())?;
+        }
+        for step in self.steps.iter() {
+            write!(
+                f,
+                "{}{}: {}",
+                "  ".repeat(step.depth + 1),
+                step.name,
+                outcome(step.matched)
+            )?;
+            match &step.detail {
+                Some(detail) => writeln!(f, " ({detail})")?,
+                None => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use chrono::{Local, TimeZone};
+
+    use super::*;
+
+    fn datetime(year: i32, month: u32, day: u32, hour: u32, min: u32) -> DateTime<FixedOffset> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, min, 0)
+            .unwrap()
+            .fixed_offset()
+    }
+
+    #[test]
+    fn explain_fields() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "dates": [
+                    {
+                        "message": "lunch",
+                        "time": { "month": ["June"], "between": { "from": "12:00", "to": "13:00" } },
+                        "condition": { "predicate": "DAY_OF_MONTH > 10", "shell": { "run": "exit 3" } },
+                        "merge_strategy": "AND"
+                    },
+                    { "condition": { "predicate": "NOT_A_VARIABLE == 1" } },
+                    { "id": "off", "message": "off", "time": { "month": ["June"] }, "enabled": false }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let explained = explain(&config, datetime(2025, 6, 14, 12, 30));
+
+        assert_eq!(explained[0].label, "lunch");
+        assert!(explained[0].matched);
+        let steps: Vec<(usize, &str, bool)> = explained[0]
+            .steps
+            .iter()
+            .map(|step| (step.depth, step.name, step.matched))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (0, "time", true),
+                (1, "month", true),
+                (1, "between", true),
+                (0, "condition", true),
+                (1, "shell", false),
+                (1, "predicate", true),
+                (1, "merge_strategy", true),
+                (0, "merge_strategy", true),
+            ]
+        );
+        assert!(
+            explained[0].steps[4]
+                .detail
+                .as_deref()
+                .is_some_and(|detail| detail.contains('3'))
+        );
+
+        assert_eq!(explained[1].label, "dates[1]");
+        assert!(!explained[1].matched);
+        assert!(
+            explained[1].steps[1]
+                .detail
+                .as_deref()
+                .is_some_and(|detail| detail.contains("failed"))
+        );
+
+        assert_eq!(explained[2].id.as_deref(), Some("off"));
+        assert!(!explained[2].matched);
+        assert_eq!(explained[2].steps, vec![Step::new(0, "enabled", false)]);
+    }
+    #[test]
+    fn explain_agrees_with_evaluation() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "dates": [
+                    { "message": "a", "time": { "day_of": { "week": ["Sat", "Sun"] } }, "cron": "* 9-17 * * *" },
+                    { "message": "b", "rrule": "DTSTART=20250101;FREQ=WEEKLY;INTERVAL=2", "condition": { "predicate": "HOUR < 12" }, "merge_strategy": "XOR" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        for day in 1..=28 {
+            for hour in [8, 11, 16] {
+                let at = datetime(2025, 2, day, hour, 0);
+                for (explained, message) in explain(&config, at).iter().zip(config.dates.iter()) {
+                    assert_eq!(
+                        explained.matched,
+                        message.try_message_at(at, None).is_some(),
+                        "{} at {at}",
+                        explained.label
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use chrono::{Datelike, NaiveDate, TimeDelta, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::time::{days_in_month, easter};
+
+/// A country with a built-in public holiday calendar. Only nationwide holidays are included, on
+/// the day they fall on (substitute days for holidays landing on a weekend are not).
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Country {
+    #[serde(alias = "au")]
+    AU,
+    #[serde(alias = "ca")]
+    CA,
+    #[serde(alias = "de")]
+    DE,
+    #[serde(alias = "fr")]
+    FR,
+    #[serde(alias = "uk")]
+    #[serde(alias = "UK")]
+    #[serde(alias = "gb")]
+    GB,
+    #[serde(alias = "us")]
+    US,
+}
+
+/// A public holiday, and the rule used to compute its date in a given year.
+#[derive(Debug, Clone, Copy)]
+pub struct Holiday {
+    pub name: &'static str,
+    rule: Rule,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Rule {
+    /// The same month and day every year.
+    Fixed(u32, u32),
+    /// The `n`th `Weekday` of the month. Negative values count from the end of the month.
+    Nth(u32, Weekday, i8),
+    /// The last `Weekday` on or before the month and day.
+    LastBefore(u32, u32, Weekday),
+    /// A number of days after Western Easter Sunday.
+    Easter(i64),
+}
+
+const fn holiday(name: &'static str, rule: Rule) -> Holiday {
+    Holiday { name, rule }
+}
+
+static AU: &[Holiday] = &[
+    holiday("New Year's Day", Rule::Fixed(1, 1)),
+    holiday("Australia Day", Rule::Fixed(1, 26)),
+    holiday("Good Friday", Rule::Easter(-2)),
+    holiday("Easter Saturday", Rule::Easter(-1)),
+    holiday("Easter Monday", Rule::Easter(1)),
+    holiday("Anzac Day", Rule::Fixed(4, 25)),
+    holiday("Christmas Day", Rule::Fixed(12, 25)),
+    holiday("Boxing Day", Rule::Fixed(12, 26)),
+];
+static CA: &[Holiday] = &[
+    holiday("New Year's Day", Rule::Fixed(1, 1)),
+    holiday("Good Friday", Rule::Easter(-2)),
+    holiday("Victoria Day", Rule::LastBefore(5, 24, Weekday::Mon)),
+    holiday("Canada Day", Rule::Fixed(7, 1)),
+    holiday("Labour Day", Rule::Nth(9, Weekday::Mon, 1)),
+    holiday(
+        "National Day for Truth and Reconciliation",
+        Rule::Fixed(9, 30),
+    ),
+    holiday("Thanksgiving", Rule::Nth(10, Weekday::Mon, 2)),
+    holiday("Remembrance Day", Rule::Fixed(11, 11)),
+    holiday("Christmas Day", Rule::Fixed(12, 25)),
+    holiday("Boxing Day", Rule::Fixed(12, 26)),
+];
+static DE: &[Holiday] = &[
+    holiday("Neujahr", Rule::Fixed(1, 1)),
+    holiday("Karfreitag", Rule::Easter(-2)),
+    holiday("Ostermontag", Rule::Easter(1)),
+    holiday("Tag der Arbeit", Rule::Fixed(5, 1)),
+    holiday("Christi Himmelfahrt", Rule::Easter(39)),
+    holiday("Pfingstmontag", Rule::Easter(50)),
+    holiday("Tag der Deutschen Einheit", Rule::Fixed(10, 3)),
+    holiday("Erster Weihnachtstag", Rule::Fixed(12, 25)),
+    holiday("Zweiter Weihnachtstag", Rule::Fixed(12, 26)),
+];
+static FR: &[Holiday] = &[
+    holiday("Jour de l'an", Rule::Fixed(1, 1)),
+    holiday("Lundi de Pâques", Rule::Easter(1)),
+    holiday("Fête du Travail", Rule::Fixed(5, 1)),
+    holiday("Victoire 1945", Rule::Fixed(5, 8)),
+    holiday("Ascension", Rule::Easter(39)),
+    holiday("Lundi de Pentecôte", Rule::Easter(50)),
+    holiday("Fête nationale", Rule::Fixed(7, 14)),
+    holiday("Assomption", Rule::Fixed(8, 15)),
+    holiday("Toussaint", Rule::Fixed(11, 1)),
+    holiday("Armistice 1918", Rule::Fixed(11, 11)),
+    holiday("Noël", Rule::Fixed(12, 25)),
+];
+static GB: &[Holiday] = &[
+    holiday("New Year's Day", Rule::Fixed(1, 1)),
+    holiday("Good Friday", Rule::Easter(-2)),
+    holiday("Easter Monday", Rule::Easter(1)),
+    holiday("Early May Bank Holiday", Rule::Nth(5, Weekday::Mon, 1)),
+    holiday("Spring Bank Holiday", Rule::Nth(5, Weekday::Mon, -1)),
+    holiday("Summer Bank Holiday", Rule::Nth(8, Weekday::Mon, -1)),
+    holiday("Christmas Day", Rule::Fixed(12, 25)),
+    holiday("Boxing Day", Rule::Fixed(12, 26)),
+];
+static US: &[Holiday] = &[
+    holiday("New Year's Day", Rule::Fixed(1, 1)),
+    holiday("Martin Luther King Jr. Day", Rule::Nth(1, Weekday::Mon, 3)),
+    holiday("Washington's Birthday", Rule::Nth(2, Weekday::Mon, 3)),
+    holiday("Memorial Day", Rule::Nth(5, Weekday::Mon, -1)),
+    holiday("Juneteenth", Rule::Fixed(6, 19)),
+    holiday("Independence Day", Rule::Fixed(7, 4)),
+    holiday("Labor Day", Rule::Nth(9, Weekday::Mon, 1)),
+    holiday("Columbus Day", Rule::Nth(10, Weekday::Mon, 2)),
+    holiday("Veterans Day", Rule::Fixed(11, 11)),
+    holiday("Thanksgiving Day", Rule::Nth(11, Weekday::Thu, 4)),
+    holiday("Christmas Day", Rule::Fixed(12, 25)),
+];
+
+impl Country {
+    /// Returns every holiday in this country's calendar.
+    pub fn holidays(&self) -> &'static [Holiday] {
+        match self {
+            Country::AU => AU,
+            Country::CA => CA,
+            Country::DE => DE,
+            Country::FR => FR,
+            Country::GB => GB,
+            Country::US => US,
+        }
+    }
+
+    /// Returns the holidays that fall on `date`.
+    pub fn holidays_on(&self, date: NaiveDate) -> impl Iterator<Item = &'static Holiday> {
+        self.holidays()
+            .iter()
+            .filter(move |holiday| holiday.date(date.year()) == Some(date))
+    }
+}
+
+impl Holiday {
+    /// Returns the date of this holiday in `year`.
+    pub fn date(&self, year: i32) -> Option<NaiveDate> {
+        match self.rule {
+            Rule::Fixed(month, day) => NaiveDate::from_ymd_opt(year, month, day),
+            Rule::Nth(month, weekday, n) if n < 0 => {
+                let last = NaiveDate::from_ymd_opt(year, month, days_in_month(year, month))?;
+                let back = last.weekday().days_since(weekday) as i64 + 7 * (-n as i64 - 1);
+                last.checked_sub_signed(TimeDelta::days(back))
+            }
+            Rule::Nth(month, weekday, n) => {
+                NaiveDate::from_weekday_of_month_opt(year, month, weekday, n as u8)
+            }
+            Rule::LastBefore(month, day, weekday) => {
+                let date = NaiveDate::from_ymd_opt(year, month, day)?;
+                let back = date.weekday().days_since(weekday) as i64;
+                date.checked_sub_signed(TimeDelta::days(back))
+            }
+            Rule::Easter(offset) => easter(year)?.checked_add_signed(TimeDelta::days(offset)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    fn names_on(country: Country, year: i32, month: u32, day: u32) -> Vec<&'static str> {
+        country
+            .holidays_on(NaiveDate::from_ymd_opt(year, month, day).unwrap())
+            .map(|holiday| holiday.name)
+            .collect()
+    }
+
+    #[test]
+    fn fixed() {
+        assert_eq!(names_on(Country::US, 2025, 7, 4), vec!["Independence Day"]);
+        assert_eq!(
+            names_on(Country::DE, 2030, 10, 3),
+            vec!["Tag der Deutschen Einheit"]
+        );
+        assert!(names_on(Country::US, 2025, 7, 5).is_empty());
+    }
+    #[test]
+    fn nth_weekday() {
+        assert_eq!(
+            names_on(Country::US, 2025, 11, 27),
+            vec!["Thanksgiving Day"]
+        );
+        assert_eq!(names_on(Country::US, 2025, 5, 26), vec!["Memorial Day"]);
+        assert_eq!(
+            names_on(Country::GB, 2025, 8, 25),
+            vec!["Summer Bank Holiday"]
+        );
+        assert_eq!(names_on(Country::CA, 2025, 10, 13), vec!["Thanksgiving"]);
+    }
+    #[test]
+    fn last_before() {
+        assert_eq!(names_on(Country::CA, 2025, 5, 19), vec!["Victoria Day"]);
+        assert_eq!(names_on(Country::CA, 2027, 5, 24), vec!["Victoria Day"]);
+    }
+    #[test]
+    fn easter_relative() {
+        assert_eq!(names_on(Country::GB, 2025, 4, 18), vec!["Good Friday"]);
+        assert_eq!(names_on(Country::FR, 2025, 5, 29), vec!["Ascension"]);
+        assert_eq!(names_on(Country::DE, 2026, 5, 25), vec!["Pfingstmontag"]);
+    }
+}
@@ -0,0 +1,443 @@
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc};
+
+use crate::{
+    config::{Config, DateSpan, SpanDate, TimeOfDaySpan, TimeRange, TimeRangeMessage},
+    errors::ConfigError,
+    rrule::parse_date,
+};
+
+/// A single content line of an iCalendar file, like `DTSTART;VALUE=DATE:20250106`.
+struct Property<'a> {
+    name: &'a str,
+    params: &'a str,
+    value: &'a str,
+}
+
+/// The start or end of an event.
+#[derive(Clone, Copy)]
+enum Moment {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+#[derive(Default)]
+struct Event<'a> {
+    uid: Option<&'a str>,
+    summary: Option<String>,
+    start: Option<Property<'a>>,
+    end: Option<Property<'a>>,
+    rrule: Option<&'a str>,
+    exdates: Vec<&'a str>,
+    /// The start of the occurrence of a recurring event that this event replaces.
+    recurrence_id: Option<&'a str>,
+}
+
+/// The events of an iCalendar file.
+#[derive(Debug, Default)]
+pub struct Calendar {
+    pub messages: Vec<TimeRangeMessage>,
+    /// Why events were left out, for events that could not be read.
+    pub warnings: Vec<String>,
+}
+
+/// Parses the `VEVENT`s of an iCalendar (`.ics`) file into `TimeRangeMessage`s, with the
+/// `SUMMARY` of each event as the message. Recurring events only match on the day each occurrence
+/// starts. Events with a `RECURRENCE-ID` replace that occurrence of the recurring event with the
+/// same `UID`. Events with a recurrence rule that is not supported are left out with a warning.
+pub fn parse_calendar(contents: &str) -> Result<Calendar, ConfigError> {
+    let lines = unfold(contents);
+    let mut events = vec![];
+    let mut event: Option<Event> = None;
+    // how deeply nested the current line is in components inside the event, like `VALARM`s.
+    let mut depth = 0usize;
+
+    for line in lines.iter() {
+        let Some(property) = parse_property(line) else {
+            continue;
+        };
+        match (property.name, property.value, event.as_mut()) {
+            ("BEGIN", "VEVENT", None) => event = Some(Event::default()),
+            ("END", "VEVENT", Some(_)) if depth == 0 => events.extend(event.take()),
+            ("END", "VEVENT", None) => {
+                return Err(ConfigError::InvalidCalendar(
+                    "END:VEVENT without BEGIN:VEVENT".to_string(),
+                ));
+            }
+            ("BEGIN", _, Some(_)) => depth += 1,
+            ("END", _, Some(_)) => depth = depth.saturating_sub(1),
+            (_, _, None) => {}
+            (_, _, Some(_)) if depth > 0 => {}
+            (name, value, Some(event)) => match name {
+                "UID" => event.uid = Some(value),
+                "SUMMARY" => event.summary = Some(unescape(value)),
+                "DTSTART" => event.start = Some(property),
+                "DTEND" => event.end = Some(property),
+                "RRULE" => event.rrule = Some(value),
+                "EXDATE" => event.exdates.push(value),
+                "RECURRENCE-ID" => event.recurrence_id = Some(value),
+                _ => {}
+            },
+        }
+    }
+
+    // the occurrences that were moved or changed are not a part of the recurring event anymore.
+    let replaced: Vec<(&str, &str)> = events
+        .iter()
+        .filter_map(|event| Some((event.uid?, event.recurrence_id?)))
+        .collect();
+    for event in events.iter_mut().filter(|event| event.rrule.is_some()) {
+        for &(uid, recurrence_id) in replaced.iter() {
+            if event.uid == Some(uid) {
+                event.exdates.push(recurrence_id);
+            }
+        }
+    }
+
+    let mut calendar = Calendar::default();
+    for event in events {
+        let summary = event.summary.clone().unwrap_or_default();
+        match event.into_message() {
+            Ok(message) => calendar.messages.extend(message),
+            Err(e @ ConfigError::InvalidRrule(..)) => calendar
+                .warnings
+                .push(format!("skipped event `{summary}`: {e}")),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(calendar)
+}
+
+/// Joins lines that were folded onto multiple lines.
+fn unfold(contents: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for line in contents.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continued), Some(last)) => last.push_str(continued),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn parse_property(line: &str) -> Option<Property<'_>> {
+    let (head, value) = line.split_once(':')?;
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some(Property {
+        name,
+        params,
+        value,
+    })
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+impl Property<'_> {
+    fn moment(&self) -> Result<Moment, ConfigError> {
+        let invalid = || ConfigError::InvalidCalendar(format!("invalid date `{}`", self.value));
+        if self.params.contains("VALUE=DATE") && !self.params.contains("VALUE=DATE-TIME")
+            || !self.value.contains('T')
+        {
+            return parse_date(self.value).map(Moment::Date).ok_or_else(invalid);
+        }
+        let naive =
+            NaiveDateTime::parse_from_str(self.value.trim_end_matches('Z'), "%Y%m%dT%H%M%S")
+                .map_err(|_| invalid())?;
+        // times in UTC are converted to local time, floating times and times with a `TZID` are
+        // taken as they are.
+        Ok(Moment::DateTime(if self.value.ends_with('Z') {
+            Utc.from_utc_datetime(&naive)
+                .with_timezone(&Local)
+                .naive_local()
+        } else {
+            naive
+        }))
+    }
+}
+
+impl Event<'_> {
+    fn into_message(self) -> Result<Option<TimeRangeMessage>, ConfigError> {
+        let (Some(summary), Some(start)) = (self.summary, self.start) else {
+            return Ok(None);
+        };
+        let start = start.moment()?;
+        let start_date = match start {
+            Moment::Date(date) => date,
+            Moment::DateTime(dt) => dt.date(),
+        };
+
+        let end = self.end.map(|end| end.moment()).transpose()?;
+        let mut time = TimeRange::default();
+        match (start, end) {
+            (Moment::DateTime(start), Some(Moment::DateTime(end)))
+                if start.date() == end.date() =>
+            {
+                time.between = Some(TimeOfDaySpan {
+                    from: start.time(),
+                    to: end.time(),
+                });
+            }
+            _ => {}
+        }
+
+        if let Some(rrule) = self.rrule {
+            let mut rule = format!("DTSTART:{}\nRRULE:{rrule}", start_date.format("%Y%m%d"));
+            for exdate in self.exdates {
+                rule.push_str(&format!("\nEXDATE:{exdate}"));
+            }
+            return Ok(Some(TimeRangeMessage {
+                message: Some(summary),
+                time: time.between.is_some().then_some(time),
+                rrule: Some(rule.parse()?),
+                ..Default::default()
+            }));
+        }
+
+        let end_date = match (start, end) {
+            (_, None) => start_date,
+            // the end of all-day events is exclusive.
+            (_, Some(Moment::Date(end))) => end.pred_opt().unwrap_or(end).max(start_date),
+            // an event ending at midnight does not include the day it ends on.
+            (_, Some(Moment::DateTime(end))) if end.time() == NaiveTime::MIN => {
+                (end.date() - TimeDelta::days(1)).max(start_date)
+            }
+            (_, Some(Moment::DateTime(end))) => end.date(),
+        };
+        time.span = Some(DateSpan {
+            from: SpanDate::Date(start_date),
+            to: SpanDate::Date(end_date),
+        });
+        Ok(Some(TimeRangeMessage {
+            message: Some(summary),
+            time: Some(time),
+            ..Default::default()
+        }))
+    }
+}
+
+/// Expands every rule in `config` over the days from `from` to `to` (inclusive), and writes an
+/// iCalendar file with one all-day event for each day a rule matches.
+pub fn export_calendar(config: &Config, from: NaiveDate, to: NaiveDate) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!(
+            "PRODID:-//occasion//occasion {}//EN",
+            env!("CARGO_PKG_VERSION")
+        ),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for date in from.iter_days().take_while(|date| *date <= to) {
+        for (idx, message) in crate::messages_on(config, date) {
+            let day = date.format("%Y%m%d");
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:{day}-{idx}@occasion"),
+                format!("DTSTAMP:{stamp}"),
+                format!("DTSTART;VALUE=DATE:{day}"),
+                format!(
+                    "DTEND;VALUE=DATE:{}",
+                    date.succ_opt().unwrap_or(date).format("%Y%m%d")
+                ),
+                format!("SUMMARY:{}", escape(&message)),
+                "TRANSP:TRANSPARENT".to_string(),
+                "END:VEVENT".to_string(),
+            ]);
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Splits a content line into lines of at most 75 octets, and terminates each with CRLF.
+fn fold(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() + 2);
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+        out.push(c);
+        len += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    static CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+SUMMARY:Company offsite\\, day one\r
+DTSTART;VALUE=DATE:20251230\r
+DTEND;VALUE=DATE:20260103\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Sprint\r
+  planning\r
+DTSTART:20250106T090000\r
+DTEND:20250106T100000\r
+RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO\r
+EXDATE:20250120T090000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Lunch\r
+DTSTART:20250107T120000\r
+DTEND:20250107T130000\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let messages = parse_calendar(CALENDAR).unwrap().messages;
+        assert_eq!(messages.len(), 3);
+
+        assert_eq!(
+            messages[0].message.as_deref(),
+            Some("Company offsite, day one")
+        );
+        assert_eq!(
+            messages[0].time.as_ref().unwrap().span,
+            Some(DateSpan {
+                from: SpanDate::Date(date(2025, 12, 30)),
+                to: SpanDate::Date(date(2026, 1, 2)),
+            })
+        );
+
+        assert_eq!(messages[1].message.as_deref(), Some("Sprint planning"));
+        let rrule = messages[1].rrule.as_ref().unwrap();
+        assert_eq!(rrule.dtstart, date(2025, 1, 6));
+        assert_eq!(rrule.exdates, vec![date(2025, 1, 20)]);
+        assert!(messages[1].time.as_ref().unwrap().between.is_some());
+
+        let lunch = messages[2].time.as_ref().unwrap();
+        assert_eq!(
+            lunch.between,
+            Some(TimeOfDaySpan {
+                from: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+                to: NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+            })
+        );
+    }
+    #[test]
+    fn export() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "dates": [
+                    { "message": "weekend; finally", "time": { "day_of": { "week": ["Sat", "Sun"] } } },
+                    { "message": "first", "time": { "day_of": { "month": [1] } } }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let exported = export_calendar(&config, date(2025, 5, 30), date(2025, 6, 2));
+        assert!(exported.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(exported.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(exported.matches("BEGIN:VEVENT").count(), 3);
+        assert!(exported.contains("UID:20250531-0@occasion\r\n"));
+        assert!(exported.contains("UID:20250601-0@occasion\r\n"));
+        assert!(exported.contains("UID:20250601-1@occasion\r\nDTSTAMP:"));
+        assert!(exported.contains("DTSTART;VALUE=DATE:20250601\r\nDTEND;VALUE=DATE:20250602\r\n"));
+        assert!(exported.contains("SUMMARY:weekend\\; finally\r\n"));
+
+        // exported calendars can be read back.
+        let parsed = parse_calendar(&exported).unwrap().messages;
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[0].message.as_deref(), Some("weekend; finally"));
+    }
+    #[test]
+    fn nested_components() {
+        let calendar = "BEGIN:VEVENT\nSUMMARY:Dentist\nDTSTART;VALUE=DATE:20250310\n\
+            BEGIN:VALARM\nACTION:DISPLAY\nSUMMARY:Reminder\nEND:VALARM\nEND:VEVENT\n";
+        let messages = parse_calendar(calendar).unwrap().messages;
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].message.as_deref(), Some("Dentist"));
+    }
+    #[test]
+    fn recurrence_id() {
+        let calendar = "BEGIN:VEVENT\nUID:standup\nSUMMARY:Standup\nDTSTART;VALUE=DATE:20250106\n\
+            RRULE:FREQ=WEEKLY\nEND:VEVENT\n\
+            BEGIN:VEVENT\nUID:standup\nRECURRENCE-ID;VALUE=DATE:20250113\nSUMMARY:Standup\n\
+            DTSTART;VALUE=DATE:20250114\nEND:VEVENT\n";
+        let messages = parse_calendar(calendar).unwrap().messages;
+        assert_eq!(messages.len(), 2);
+        let rrule = messages[0].rrule.as_ref().unwrap();
+        assert!(rrule.matches(date(2025, 1, 6)));
+        assert!(!rrule.matches(date(2025, 1, 13)));
+        assert!(rrule.matches(date(2025, 1, 20)));
+        assert_eq!(
+            messages[1].time.as_ref().unwrap().span,
+            Some(DateSpan {
+                from: SpanDate::Date(date(2025, 1, 14)),
+                to: SpanDate::Date(date(2025, 1, 14)),
+            })
+        );
+    }
+    #[test]
+    fn unsupported_rrule() {
+        let calendar = "BEGIN:VEVENT\nSUMMARY:Hourly\nDTSTART:20250106T090000\n\
+            RRULE:FREQ=HOURLY\nEND:VEVENT\n\
+            BEGIN:VEVENT\nSUMMARY:Lunch\nDTSTART;VALUE=DATE:20250107\nEND:VEVENT\n";
+        let calendar = parse_calendar(calendar).unwrap();
+        assert_eq!(calendar.messages.len(), 1);
+        assert_eq!(calendar.messages[0].message.as_deref(), Some("Lunch"));
+        assert_eq!(calendar.warnings.len(), 1);
+        assert!(calendar.warnings[0].starts_with("skipped event `Hourly`"));
+    }
+    #[test]
+    fn fold_long_lines() {
+        let line = format!("SUMMARY:{}", "ä".repeat(50));
+        let folded = fold(&line);
+        assert!(folded.split("\r\n").all(|l| l.len() <= 75));
+        assert_eq!(unfold(&folded), vec![line]);
+    }
+    #[test]
+    fn invalid() {
+        let broken = "BEGIN:VEVENT\nSUMMARY:hai\nDTSTART:2025-01-01\nEND:VEVENT\n";
+        assert!(matches!(
+            parse_calendar(broken),
+            Err(ConfigError::InvalidCalendar(_))
+        ));
+    }
+}
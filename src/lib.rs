@@ -1,35 +1,178 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate};
 use config::{Config, MultipleBehavior};
+use render::Occasion;
 
+pub mod calendar;
 pub mod config;
+pub mod cron;
 pub mod errors;
+pub mod explain;
+pub mod holidays;
+pub mod ics;
+pub mod render;
+pub mod rrule;
+pub mod style;
 pub mod time;
 
 pub fn output_of(config: &Config) -> String {
-    let behavior = &config.multiple_behavior;
+    output_at(config, Local::now().fixed_offset())
+}
+
+/// Like `output_of`, but evaluates every rule as if it was `at`.
+pub fn output_at(config: &Config, at: DateTime<FixedOffset>) -> String {
+    render::plain(&occasions_at(config, at), config.seperator())
+}
 
-    let outputs: Vec<String> = config
+/// Returns every rule that matches at `at`, with `multiple_behavior` used to pick which ones to
+/// keep.
+pub fn occasions_at(config: &Config, at: DateTime<FixedOffset>) -> Vec<Occasion> {
+    let mut rng = config.rng(at);
+    let occasions = all_occasions_at(config, at, &mut rng);
+    select(config, occasions, &mut rng)
+}
+
+/// Returns every rule that matches at `at`, ignoring `multiple_behavior`. Messages out of
+/// `messages` are picked with `rng`.
+pub fn all_occasions_at(
+    config: &Config,
+    at: DateTime<FixedOffset>,
+    rng: &mut fastrand::Rng,
+) -> Vec<Occasion> {
+    config
         .dates
         .iter()
-        .filter_map(|message| message.try_message(config.week_start_day))
+        .enumerate()
+        .filter_map(|(index, date)| {
+            date.try_message_with_kind_at(at, config.week_start_day, rng)
+                .map(|(message, kind)| Occasion {
+                    index,
+                    id: date.id.clone(),
+                    message,
+                    source: date.source.path.clone(),
+                    kind,
+                    class: date.class.clone(),
+                    style: date.style.clone(),
+                })
+        })
+        .collect()
+}
+
+/// Picks the occasions to show according to `multiple_behavior`, using `rng` for `random` and
+/// `weighted_random`. Occasions hidden by `suppresses` or `group` are dropped first.
+pub fn select(config: &Config, occasions: Vec<Occasion>, rng: &mut fastrand::Rng) -> Vec<Occasion> {
+    let priority = |occasion: &Occasion| {
+        config
+            .dates
+            .get(occasion.index)
+            .map_or(0, |date| date.priority)
+    };
+    let mut occasions = resolve_conflicts(config, occasions);
+    match config.multiple_behavior {
+        Some(MultipleBehavior::All { .. }) | None => occasions,
+        Some(MultipleBehavior::First) => {
+            occasions.truncate(1);
+            occasions
+        }
+        Some(MultipleBehavior::Last) => occasions.pop().into_iter().collect(),
+        Some(MultipleBehavior::Random) if occasions.is_empty() => occasions,
+        Some(MultipleBehavior::Random) => {
+            vec![occasions.swap_remove(rng.usize(..occasions.len()))]
+        }
+        Some(MultipleBehavior::Highest) => {
+            occasions.sort_by_key(|occasion| std::cmp::Reverse(priority(occasion)));
+            occasions.truncate(1);
+            occasions
+        }
+        Some(MultipleBehavior::TopN { n, .. }) => {
+            occasions.sort_by_key(|occasion| std::cmp::Reverse(priority(occasion)));
+            occasions.truncate(n);
+            occasions
+        }
+        Some(MultipleBehavior::WeightedRandom) => {
+            let weights: Vec<u64> = occasions
+                .iter()
+                .map(|occasion| ((priority(occasion) as i64).max(-1) + 1) as u64)
+                .collect();
+            let idx = match weights.iter().sum() {
+                0 if occasions.is_empty() => return occasions,
+                0 => rng.usize(..occasions.len()),
+                total => {
+                    let mut roll = rng.u64(..total);
+                    weights
+                        .iter()
+                        .position(|&weight| {
+                            if roll < weight {
+                                true
+                            } else {
+                                roll -= weight;
+                                false
+                            }
+                        })
+                        .unwrap_or(0)
+                }
+            };
+            vec![occasions.swap_remove(idx)]
+        }
+    }
+}
+
+/// Drops the occasions whose rule is in the `suppresses` of another matching rule, and every
+/// occasion but the best one of each `group`.
+fn resolve_conflicts(config: &Config, occasions: Vec<Occasion>) -> Vec<Occasion> {
+    let rule = |occasion: &Occasion| config.dates.get(occasion.index);
+    let suppressed: HashSet<&str> = occasions
+        .iter()
+        .filter_map(rule)
+        .flat_map(|date| date.suppresses.iter().map(String::as_str))
         .collect();
-    match behavior {
-        Some(MultipleBehavior::All { seperator }) => outputs
-            .into_iter()
-            .reduce(|mut str, curr| {
-                str.push_str(seperator);
-                str.push_str(&curr);
-                str
-            })
-            .unwrap_or_default(),
-        None => outputs
-            .into_iter()
-            .reduce(|mut str, curr| {
-                str.push_str(&curr);
-                str
-            })
-            .unwrap_or_default(),
-        Some(MultipleBehavior::First) => outputs.first().map_or("", |v| v).to_string(),
-        Some(MultipleBehavior::Last) => outputs.last().map_or("", |v| v).to_string(),
-        Some(MultipleBehavior::Random) => outputs[fastrand::usize(..outputs.len())].clone(),
+    let mut best: HashMap<&str, &Occasion> = HashMap::new();
+    let mut unsuppressed = vec![];
+    for occasion in occasions.iter() {
+        let Some(date) = rule(occasion) else {
+            unsuppressed.push(occasion);
+            continue;
+        };
+        if date.id.as_deref().is_some_and(|id| suppressed.contains(id)) {
+            continue;
+        }
+        if let Some(group) = date.group.as_deref() {
+            let better = best
+                .get(group)
+                .and_then(|other| rule(other))
+                .is_none_or(|other| date.priority > other.priority);
+            if better {
+                best.insert(group, occasion);
+            }
+        }
+        unsuppressed.push(occasion);
     }
+    unsuppressed
+        .into_iter()
+        .filter(
+            |occasion| match rule(occasion).and_then(|date| date.group.as_deref()) {
+                Some(group) => best
+                    .get(group)
+                    .is_some_and(|best| best.index == occasion.index),
+                None => true,
+            },
+        )
+        .cloned()
+        .collect()
+}
+
+/// Returns the message of every rule that matches at any time during `date`, along with the index
+/// of the rule in `config.dates`. `multiple_behavior` is not applied.
+pub fn messages_on(config: &Config, date: NaiveDate) -> Vec<(usize, String)> {
+    config
+        .dates
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, message)| {
+            message
+                .try_message_on(date, config.week_start_day)
+                .map(|msg| (idx, msg))
+        })
+        .collect()
 }
@@ -1,19 +1,151 @@
-use clap::Parser;
+use std::{
+    io::{IsTerminal, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
 use occasion::{config::Config, errors::ConfigError};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 struct Cli {
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     /// Prints any error messages instead of failing silently.
     check: bool,
+    #[arg(long, alias = "date", global = true, value_parser = parse_at)]
+    /// Uses this date and time instead of now, like `2025-12-24T09:00` or `2025-12-24`.
+    at: Option<DateTime<FixedOffset>>,
+    #[arg(long, global = true)]
+    /// Ignores the `style` of every message. Also enabled by setting `NO_COLOR`.
+    no_color: bool,
+    #[arg(long, value_enum, default_value_t = Format::Plain)]
+    /// How to print the matching messages.
+    format: Format,
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// The messages, joined according to `multiple_behavior`, with ANSI escape codes for their
+    /// styles.
+    Plain,
+    /// A JSON array of the matching messages, with the index and source file of each rule.
+    Json,
+    /// The JSON output of a Waybar custom module, with every match in the tooltip.
+    Waybar,
+    /// Plain text with Polybar formatting tags for each message's style.
+    Polybar,
+    /// Plain text with tmux style markup for each message's style.
+    Tmux,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Expands every rule over a range of days, and exports each match as an all-day event.
+    Export {
+        #[arg(long, required = true)]
+        /// Exports an iCalendar (.ics) file.
+        ics: bool,
+        #[arg(long)]
+        /// The first day to export, as YYYY-MM-DD.
+        from: NaiveDate,
+        #[arg(long)]
+        /// The last day to export (inclusive), as YYYY-MM-DD.
+        to: NaiveDate,
+        #[arg(short, long)]
+        /// Writes the calendar to this file instead of stdout.
+        output: Option<PathBuf>,
+    },
+    /// Lists the next days each rule will match on, starting today.
+    Next {
+        #[arg(short = 'n', long, default_value_t = 1)]
+        /// How many days to list for each rule.
+        count: usize,
+        #[arg(long, default_value_t = 366)]
+        /// How many days ahead to look.
+        days: u32,
+        #[arg(long)]
+        /// Ignores shell conditions instead of running them for every day. Rules with only a
+        /// shell condition will never match.
+        skip_shell: bool,
+    },
+    /// Keeps running and prints the matching messages as i3bar (or swaybar) protocol blocks,
+    /// reloading the config every interval. Use it as the `status_command` of your bar.
+    I3bar {
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..))]
+        /// How often to check for new messages, in seconds.
+        interval: u64,
+    },
+    /// Shows why each rule did or did not match.
+    Explain,
+    /// Shows a calendar with every day that has a matching rule highlighted.
+    Calendar {
+        #[arg(long, value_parser = parse_month, conflicts_with = "year")]
+        /// The month to show, as YYYY-MM. Defaults to the current month.
+        month: Option<(i32, u32)>,
+        #[arg(long)]
+        /// Shows every month of this year.
+        year: Option<i32>,
+    },
+}
+
+/// Parses an RFC 3339 timestamp, or a date and time (or just a date) in local time.
+fn parse_at(value: &str) -> Result<DateTime<FixedOffset>, String> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt);
+    }
+    let naive = ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(value, fmt).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .ok_or(format!(
+            "`{value}` is not a date like `2025-12-24` or a date and time like `2025-12-24T09:00`"
+        ))?;
+    naive
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|dt| dt.fixed_offset())
+        .ok_or(format!("`{value}` does not exist in the local timezone"))
+}
+
+/// Parses a month like `2025-06`.
+fn parse_month(value: &str) -> Result<(i32, u32), String> {
+    let invalid = || format!("`{value}` is not a month like `2025-06`");
+    let (year, month) = value.split_once('-').ok_or_else(invalid)?;
+    let year = year.parse().map_err(|_| invalid())?;
+    let month = month
+        .parse()
+        .ok()
+        .filter(|m| (1..=12).contains(m))
+        .ok_or_else(invalid)?;
+    Ok((year, month))
+}
+
+/// Removes the `style` of every rule, for `--no-color`.
+fn strip_styles(mut config: Config) -> Config {
+    for date in config.dates.iter_mut() {
+        date.style = None;
+    }
+    config
 }
 
 fn main() -> Result<(), ConfigError> {
     let flags = Cli::parse();
+    let no_color = flags.no_color || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    if no_color {
+        colored::control::set_override(false);
+    }
 
     let config = match Config::load_or_default(flags.check) {
+        Ok(config) if no_color => strip_styles(config),
         Ok(config) => config,
         Err(e) if flags.check => {
             eprintln!("{}", format!("{e}").red());
@@ -21,6 +153,160 @@ fn main() -> Result<(), ConfigError> {
         }
         _ => return Ok(()),
     };
-    println!("{}", occasion::output_of(&config));
+    match flags.command {
+        None => {
+            let at = flags.at.unwrap_or_else(|| Local::now().fixed_offset());
+            match flags.format {
+                Format::Plain => {
+                    let occasions = occasion::occasions_at(&config, at);
+                    // escape codes only make sense in a terminal, not when piped into a file.
+                    match !no_color && std::io::stdout().is_terminal() {
+                        true => {
+                            println!("{}", occasion::render::ansi(&occasions, config.seperator()))
+                        }
+                        false => {
+                            println!(
+                                "{}",
+                                occasion::render::plain(&occasions, config.seperator())
+                            )
+                        }
+                    }
+                }
+                Format::Polybar => {
+                    let occasions = occasion::occasions_at(&config, at);
+                    println!(
+                        "{}",
+                        occasion::render::polybar(&occasions, config.seperator())
+                    )
+                }
+                Format::Tmux => {
+                    let occasions = occasion::occasions_at(&config, at);
+                    println!("{}", occasion::render::tmux(&occasions, config.seperator()))
+                }
+                Format::Json => {
+                    let occasions = occasion::occasions_at(&config, at);
+                    println!("{}", occasion::render::json(&occasions))
+                }
+                Format::Waybar => {
+                    let mut rng = config.rng(at);
+                    let all = occasion::all_occasions_at(&config, at, &mut rng);
+                    let shown = occasion::select(&config, all.clone(), &mut rng);
+                    println!(
+                        "{}",
+                        occasion::render::waybar(
+                            &shown,
+                            &all,
+                            config.seperator(),
+                            config.dates.iter().filter(|date| date.is_enabled()).count()
+                        )
+                    )
+                }
+            }
+        }
+        Some(Commands::Export {
+            ics: _,
+            from,
+            to,
+            output,
+        }) => {
+            if from > to {
+                return Err(ConfigError::InvalidDate(format!(
+                    "--from {from} is later than --to {to}"
+                )));
+            }
+            let calendar = occasion::ics::export_calendar(&config, from, to);
+            match output {
+                Some(path) => std::fs::write(path, calendar)?,
+                None => print!("{calendar}"),
+            }
+        }
+        Some(Commands::I3bar { interval }) => {
+            let mut config = config;
+            let mut stdout = std::io::stdout().lock();
+            writeln!(stdout, "{}\n[", occasion::render::i3bar_header())?;
+            let mut first = true;
+            loop {
+                let at = Local::now().fixed_offset();
+                let blocks = occasion::render::i3bar(&occasion::occasions_at(&config, at));
+                let comma = if first { "" } else { "," };
+                // the bar has exited if stdout is closed.
+                if writeln!(stdout, "{comma}{blocks}")
+                    .and_then(|_| stdout.flush())
+                    .is_err()
+                {
+                    break;
+                }
+                first = false;
+
+                // wake up at the start of the next interval, so minutes are not skipped.
+                let elapsed = at.timestamp().rem_euclid(interval as i64) as u64;
+                std::thread::sleep(Duration::from_secs(interval - elapsed));
+                if let Ok(reloaded) = Config::load_or_default(false) {
+                    config = match no_color {
+                        true => strip_styles(reloaded),
+                        false => reloaded,
+                    };
+                }
+            }
+        }
+        Some(Commands::Explain) => {
+            let at = flags.at.unwrap_or_else(|| Local::now().fixed_offset());
+            for explanation in occasion::explain::explain(&config, at) {
+                print!("{explanation}");
+            }
+        }
+        Some(Commands::Calendar { month, year }) => {
+            let today = flags
+                .at
+                .unwrap_or_else(|| Local::now().fixed_offset())
+                .date_naive();
+            let months: Vec<(i32, u32)> = match (month, year) {
+                (Some(month), _) => vec![month],
+                (None, Some(year)) => (1..=12).map(|month| (year, month)).collect(),
+                (None, None) => vec![(today.year(), today.month())],
+            };
+            let rendered: Vec<String> = months
+                .into_iter()
+                .map(|(year, month)| {
+                    occasion::calendar::render_month(&config, year, month, today, !no_color)
+                })
+                .collect();
+            print!("{}", rendered.join("\n"));
+        }
+        Some(Commands::Next {
+            count,
+            days,
+            skip_shell,
+        }) => {
+            let today = flags.at.unwrap_or_else(|| Local::now().fixed_offset());
+            for (idx, message) in config.dates.iter().enumerate() {
+                if !message.is_enabled() {
+                    continue;
+                }
+                let dates = message.next_dates(
+                    today.date_naive(),
+                    days,
+                    count,
+                    config.week_start_day,
+                    skip_shell,
+                );
+                let label = message
+                    .label()
+                    .map_or(format!("dates[{idx}]"), str::to_string);
+                if dates.is_empty() {
+                    println!(
+                        "{label}: {}",
+                        format!("not in the next {days} days").dimmed()
+                    );
+                } else {
+                    let dates: Vec<String> = dates
+                        .iter()
+                        .map(|date| date.format("%a %Y-%m-%d").to_string())
+                        .collect();
+                    println!("{label}: {}", dates.join(", "));
+                }
+            }
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,391 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::style::{Color, Style, escape_pango};
+
+/// A message of a rule that matched.
+#[derive(Debug, Serialize, PartialEq, Eq, Clone)]
+pub struct Occasion {
+    /// The index of the rule in `Config.dates`.
+    pub index: usize,
+    /// The `id` of the rule.
+    pub id: Option<String>,
+    pub message: String,
+    /// The file the rule was loaded from.
+    pub source: Option<PathBuf>,
+    pub kind: MessageKind,
+    /// The `class` of the rule.
+    pub class: Option<String>,
+    pub style: Option<Style>,
+}
+
+/// Where the text of an `Occasion` came from.
+#[derive(Debug, Serialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageKind {
+    /// The `message` of the rule, either because it has no `command` or because the command
+    /// failed without output.
+    Message,
+    /// The output of the rule's `command`.
+    Command,
+}
+
+/// Joins the messages of `occasions` with `seperator`.
+pub fn plain(occasions: &[Occasion], seperator: &str) -> String {
+    join(occasions, seperator, |occasion| occasion.message.clone())
+}
+
+/// Renders each of `occasions` with `render`, and joins them with `seperator`.
+fn join(occasions: &[Occasion], seperator: &str, render: impl Fn(&Occasion) -> String) -> String {
+    occasions
+        .iter()
+        .map(render)
+        .collect::<Vec<_>>()
+        .join(seperator)
+}
+
+/// Joins the messages of `occasions` with `seperator`, with ANSI escape codes for their `style`.
+pub fn ansi(occasions: &[Occasion], seperator: &str) -> String {
+    join(occasions, seperator, |occasion| {
+        occasion
+            .style
+            .clone()
+            .unwrap_or_default()
+            .ansi(&occasion.message)
+    })
+}
+
+/// Joins the messages of `occasions` with `seperator`, using Polybar's formatting tags for their
+/// `style`. Polybar can only make text bold or italic by switching fonts, so only `underline` and
+/// the colors are used.
+pub fn polybar(occasions: &[Occasion], seperator: &str) -> String {
+    let escape = |text: &str| text.replace('%', "%%");
+    join(occasions, &escape(seperator), |occasion| {
+        let mut text = escape(&occasion.message);
+        let style = occasion.style.clone().unwrap_or_default();
+        if let Some(fg) = style.fg {
+            text = format!("%{{F{}}}{text}%{{F-}}", fg.hex());
+        }
+        if let Some(bg) = style.bg {
+            text = format!("%{{B{}}}{text}%{{B-}}", bg.hex());
+        }
+        if style.underline {
+            text = format!("%{{+u}}{text}%{{-u}}");
+        }
+        text
+    })
+}
+
+/// Joins the messages of `occasions` with `seperator`, using tmux's `#[...]` style markup for
+/// their `style`.
+pub fn tmux(occasions: &[Occasion], seperator: &str) -> String {
+    let escape = |text: &str| text.replace('#', "##");
+    let color = |color: Color| match color {
+        Color::Ansi(idx) if idx >= 8 => format!("bright{}", Color::Ansi(idx - 8)),
+        other => other.to_string(),
+    };
+    join(occasions, &escape(seperator), |occasion| {
+        let text = escape(&occasion.message);
+        let style = occasion.style.clone().unwrap_or_default();
+        let attributes: Vec<String> = [
+            style.fg.map(|fg| format!("fg={}", color(fg))),
+            style.bg.map(|bg| format!("bg={}", color(bg))),
+            style.bold.then(|| "bold".to_string()),
+            style.italic.then(|| "italics".to_string()),
+            style.underline.then(|| "underscore".to_string()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        match attributes.is_empty() {
+            true => text,
+            false => format!("#[{}]{text}#[default]", attributes.join(",")),
+        }
+    })
+}
+
+/// Renders `occasions` as a JSON array.
+pub fn json(occasions: &[Occasion]) -> String {
+    serde_json::to_string(occasions).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Renders the output of a Waybar custom module with `"return-type": "json"`. `text` is `shown`
+/// joined with `seperator`, with the `style` of each message as Pango markup, while `tooltip`
+/// lists every match in `all`, one per line. `class` has the classes of every shown message, and
+/// `alt` the class of the first one (`default` if it has none, `none` if nothing is shown).
+/// `percentage` is the share of the `rules`, the number of enabled rules, that matched.
+pub fn waybar(shown: &[Occasion], all: &[Occasion], seperator: &str, rules: usize) -> String {
+    let mut classes: Vec<&str> = vec![];
+    for class in shown
+        .iter()
+        .filter_map(|occasion| occasion.class.as_deref())
+    {
+        if !classes.contains(&class) {
+            classes.push(class);
+        }
+    }
+    let alt = match shown.first() {
+        None => "none",
+        Some(first) => first.class.as_deref().unwrap_or("default"),
+    };
+    let percentage = match rules {
+        0 => 0,
+        rules => all.len() * 100 / rules,
+    };
+    let pango = |occasion: &Occasion| {
+        occasion
+            .style
+            .clone()
+            .unwrap_or_default()
+            .pango(&occasion.message, true)
+    };
+    json!({
+        "text": join(shown, &escape_pango(seperator), pango),
+        "tooltip": join(all, "\n", pango),
+        "class": classes,
+        "alt": alt,
+        "percentage": percentage,
+    })
+    .to_string()
+}
+
+/// The header that starts the i3bar protocol. It has to be followed by `[` and an endless list of
+/// status lines from `i3bar`, seperated by commas.
+pub fn i3bar_header() -> String {
+    json!({ "version": 1 }).to_string()
+}
+
+/// Renders one status line of the i3bar protocol, with a block for each of `occasions`.
+/// `short_text`, which is used when the bar runs out of space, is the message cut down to at most
+/// 12 characters. Blocks with bold, italic or underlined text use Pango markup.
+pub fn i3bar(occasions: &[Occasion]) -> String {
+    let blocks: Vec<serde_json::Value> = occasions
+        .iter()
+        .map(|occasion| {
+            let mut block = json!({
+                "full_text": occasion.message,
+                "short_text": shorten(&occasion.message, 12),
+                "name": "occasion",
+                "instance": occasion.index.to_string(),
+            });
+            let style = occasion.style.clone().unwrap_or_default();
+            if style.has_attributes() {
+                block["full_text"] = style.pango(&occasion.message, false).into();
+                block["short_text"] = style.pango(&shorten(&occasion.message, 12), false).into();
+                block["markup"] = "pango".into();
+            }
+            if let Some(fg) = style.fg {
+                block["color"] = fg.hex().into();
+            }
+            if let Some(bg) = style.bg {
+                block["background"] = bg.hex().into();
+            }
+            block
+        })
+        .collect();
+    serde_json::Value::from(blocks).to_string()
+}
+
+/// Cuts `text` down to `max` characters, ending with `…` if anything was cut.
+fn shorten(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        None => text.to_string(),
+        Some(_) => {
+            let mut short: String = text.chars().take(max - 1).collect();
+            short.push('…');
+            short
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn render_json() {
+        let occasions = vec![
+            Occasion {
+                index: 0,
+                id: None,
+                message: "hai".to_string(),
+                source: Some(PathBuf::from("/home/alice/.config/occasions.json")),
+                kind: MessageKind::Message,
+                class: None,
+                style: None,
+            },
+            Occasion {
+                index: 3,
+                id: Some("friday".to_string()),
+                message: "it's \"friday\"".to_string(),
+                source: None,
+                kind: MessageKind::Command,
+                class: Some("friday".to_string()),
+                style: Some(Style {
+                    fg: Some(Color::Ansi(1)),
+                    bold: true,
+                    ..Default::default()
+                }),
+            },
+        ];
+        assert_eq!(
+            json(&occasions),
+            r#"[{"index":0,"id":null,"message":"hai","source":"/home/alice/.config/occasions.json","kind":"message","class":null,"style":null},{"index":3,"id":"friday","message":"it's \"friday\"","source":null,"kind":"command","class":"friday","style":{"fg":"red","bg":null,"bold":true,"italic":false,"underline":false}}]"#
+        );
+        assert_eq!(json(&[]), "[]");
+        assert_eq!(plain(&occasions, " | "), "hai | it's \"friday\"");
+    }
+    #[test]
+    fn render_waybar() {
+        let occasion = |index: usize, message: &str, class: Option<&str>| Occasion {
+            index,
+            id: None,
+            message: message.to_string(),
+            source: None,
+            kind: MessageKind::Message,
+            class: class.map(str::to_string),
+            style: None,
+        };
+        let all = vec![
+            occasion(0, "Christmas Day", Some("holiday")),
+            occasion(1, "water the plants", Some("reminder")),
+            occasion(2, "hai", None),
+        ];
+        let rendered: serde_json::Value =
+            serde_json::from_str(&waybar(&all[1..2], &all, "", 4)).unwrap();
+        assert_eq!(
+            rendered,
+            json!({
+                "text": "water the plants",
+                "tooltip": "Christmas Day\nwater the plants\nhai",
+                "class": ["reminder"],
+                "alt": "reminder",
+                "percentage": 75,
+            })
+        );
+
+        let rendered: serde_json::Value = serde_json::from_str(&waybar(&[], &[], "", 0)).unwrap();
+        assert_eq!(rendered["text"], "");
+        assert_eq!(rendered["alt"], "none");
+        assert_eq!(rendered["percentage"], 0);
+    }
+    #[test]
+    fn render_polybar_and_tmux() {
+        let occasions = vec![
+            Occasion {
+                index: 0,
+                id: None,
+                message: "100% #1".to_string(),
+                source: None,
+                kind: MessageKind::Message,
+                class: None,
+                style: Some(Style {
+                    fg: Some("bright_red".parse().unwrap()),
+                    bg: Some("#102030".parse().unwrap()),
+                    bold: true,
+                    ..Default::default()
+                }),
+            },
+            Occasion {
+                index: 1,
+                id: None,
+                message: "hai".to_string(),
+                source: None,
+                kind: MessageKind::Message,
+                class: None,
+                style: Some(Style {
+                    fg: Some("blue".parse().unwrap()),
+                    bg: None,
+                    underline: true,
+                    ..Default::default()
+                }),
+            },
+            Occasion {
+                index: 2,
+                id: None,
+                message: "plain".to_string(),
+                source: None,
+                kind: MessageKind::Message,
+                class: None,
+                style: None,
+            },
+        ];
+        assert_eq!(
+            polybar(&occasions, " % "),
+            "%{B#102030}%{F#ff0000}100%% #1%{F-}%{B-} %% %{+u}%{F#0000ee}hai%{F-}%{-u} %% plain"
+        );
+        assert_eq!(
+            tmux(&occasions, " # "),
+            "#[fg=brightred,bg=#102030,bold]100% ##1#[default] ## #[fg=blue,underscore]hai#[default] ## plain"
+        );
+        assert_eq!(
+            ansi(&occasions, " # "),
+            "\x1b[1;91;48;2;16;32;48m100% #1\x1b[0m # \x1b[4;34mhai\x1b[0m # plain"
+        );
+    }
+    #[test]
+    fn render_i3bar() {
+        let occasions = vec![
+            Occasion {
+                index: 2,
+                id: None,
+                message: "Merry Christmas everyone!".to_string(),
+                source: None,
+                kind: MessageKind::Message,
+                class: None,
+                style: Some(Style {
+                    fg: Some("red".parse().unwrap()),
+                    bg: Some("#102030".parse().unwrap()),
+                    ..Default::default()
+                }),
+            },
+            Occasion {
+                index: 5,
+                id: None,
+                message: "hai".to_string(),
+                source: None,
+                kind: MessageKind::Message,
+                class: None,
+                style: None,
+            },
+            Occasion {
+                index: 6,
+                id: None,
+                message: "<3".to_string(),
+                source: None,
+                kind: MessageKind::Message,
+                class: None,
+                style: Some(Style {
+                    italic: true,
+                    ..Default::default()
+                }),
+            },
+        ];
+        let rendered: serde_json::Value = serde_json::from_str(&i3bar(&occasions)).unwrap();
+        assert_eq!(
+            rendered,
+            json!([
+                {
+                    "full_text": "Merry Christmas everyone!",
+                    "short_text": "Merry Chris…",
+                    "name": "occasion",
+                    "instance": "2",
+                    "color": "#cd0000",
+                    "background": "#102030",
+                },
+                { "full_text": "hai", "short_text": "hai", "name": "occasion", "instance": "5" },
+                {
+                    "full_text": "<span style=\"italic\">&lt;3</span>",
+                    "short_text": "<span style=\"italic\">&lt;3</span>",
+                    "markup": "pango",
+                    "name": "occasion",
+                    "instance": "6",
+                },
+            ])
+        );
+        assert_eq!(i3bar(&[]), "[]");
+        assert_eq!(i3bar_header(), r#"{"version":1}"#);
+    }
+}
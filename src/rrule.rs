@@ -0,0 +1,245 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::ConfigError;
+
+/// A parsed RFC 5545 recurrence rule, like
+/// `DTSTART:20250106\nRRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO`. `DTSTART` may also be written as a
+/// part of the rule itself (`DTSTART=20250106;FREQ=WEEKLY`). Occurrences are whole days, so the
+/// time of day in `DTSTART` and `UNTIL` is ignored.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(try_from = "String", into = "String")]
+pub struct RecurrenceRule {
+    source: String,
+    pub dtstart: NaiveDate,
+    pub freq: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<NaiveDate>,
+    /// Weekdays, with an optional ordinal (`2TU`, `-1FR`).
+    pub by_day: Vec<(Option<i32>, Weekday)>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_set_pos: Vec<i32>,
+    pub week_start: Weekday,
+    pub exdates: Vec<NaiveDate>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Parses an RFC 5545 `DATE` or `DATE-TIME` value, ignoring the time.
+pub(crate) fn parse_date(value: &str) -> Option<NaiveDate> {
+    let date = value.get(..8)?;
+    NaiveDate::parse_from_str(date, "%Y%m%d").ok()
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    match value {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn parse_list<T: FromStr>(value: &str) -> Option<Vec<T>> {
+    value.split(',').map(|v| v.parse().ok()).collect()
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| ConfigError::InvalidRrule(s.to_string(), reason);
+
+        let mut dtstart = None;
+        let mut exdates = vec![];
+        let mut parts = vec![];
+        for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let (name, value) = match line.split_once(':') {
+                Some((name, value)) => (name, value),
+                None => ("RRULE", line),
+            };
+            // property parameters, like `DTSTART;VALUE=DATE:20250106`, are not needed.
+            match name.split(';').next().unwrap_or_default() {
+                "DTSTART" => dtstart = Some(value),
+                "EXDATE" => {
+                    for date in value.split(',') {
+                        exdates.push(
+                            parse_date(date)
+                                .ok_or(invalid(format!("`{date}` is not a valid EXDATE")))?,
+                        );
+                    }
+                }
+                "RRULE" => parts.extend(value.split(';').filter(|p| !p.is_empty())),
+                other => return Err(invalid(format!("unknown property `{other}`"))),
+            }
+        }
+
+        let mut rule = RecurrenceRule {
+            source: s.to_string(),
+            dtstart: NaiveDate::MIN,
+            freq: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: vec![],
+            by_month_day: vec![],
+            by_month: vec![],
+            by_set_pos: vec![],
+            week_start: Weekday::Mon,
+            exdates,
+        };
+        let mut freq = None;
+        for part in parts {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or(invalid(format!("`{part}` is not a KEY=VALUE pair")))?;
+            let bad_value = || invalid(format!("invalid value `{value}` for {key}"));
+            match key {
+                "DTSTART" => dtstart = Some(value),
+                "FREQ" => {
+                    freq = Some(match value {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(bad_value()),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|&i| i > 0)
+                        .ok_or_else(bad_value)?
+                }
+                "COUNT" => rule.count = Some(value.parse().map_err(|_| bad_value())?),
+                "UNTIL" => rule.until = Some(parse_date(value).ok_or_else(bad_value)?),
+                "WKST" => rule.week_start = parse_weekday(value).ok_or_else(bad_value)?,
+                "BYMONTH" => {
+                    rule.by_month = parse_list(value)
+                        .filter(|months: &Vec<u32>| months.iter().all(|m| (1..=12).contains(m)))
+                        .ok_or_else(bad_value)?
+                }
+                "BYMONTHDAY" => {
+                    rule.by_month_day = parse_list(value)
+                        .filter(|days: &Vec<i32>| {
+                            days.iter().all(|d| *d != 0 && (-31..=31).contains(d))
+                        })
+                        .ok_or_else(bad_value)?
+                }
+                "BYSETPOS" => {
+                    rule.by_set_pos = parse_list(value)
+                        .filter(|pos: &Vec<i32>| !pos.contains(&0))
+                        .ok_or_else(bad_value)?
+                }
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        // the weekday is the last two characters, which may not be two bytes.
+                        let split = day.char_indices().rev().nth(1).map_or(0, |(idx, _)| idx);
+                        let (ordinal, weekday) = day.split_at(split);
+                        let weekday = parse_weekday(weekday).ok_or_else(bad_value)?;
+                        let ordinal = match ordinal {
+                            "" => None,
+                            ord => {
+                                Some(ord.parse().ok().filter(|&o| o != 0).ok_or_else(bad_value)?)
+                            }
+                        };
+                        rule.by_day.push((ordinal, weekday));
+                    }
+                }
+                other => return Err(invalid(format!("unsupported rule part `{other}`"))),
+            }
+        }
+
+        rule.freq = freq.ok_or(invalid("missing FREQ".to_string()))?;
+        let dtstart = dtstart.ok_or(invalid("missing DTSTART".to_string()))?;
+        rule.dtstart =
+            parse_date(dtstart).ok_or(invalid(format!("`{dtstart}` is not a valid DTSTART")))?;
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err(invalid("COUNT and UNTIL cannot both be set".to_string()));
+        }
+        Ok(rule)
+    }
+}
+impl TryFrom<String> for RecurrenceRule {
+    type Error = ConfigError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+impl Display for RecurrenceRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+impl From<RecurrenceRule> for String {
+    fn from(value: RecurrenceRule) -> Self {
+        value.source
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let rule: RecurrenceRule =
+            "DTSTART;VALUE=DATE:20250106\nRRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,-1FR;COUNT=4"
+                .parse()
+                .unwrap();
+        assert_eq!(rule.dtstart, NaiveDate::from_ymd_opt(2025, 1, 6).unwrap());
+        assert_eq!(rule.freq, Frequency::Weekly);
+        assert_eq!(rule.interval, 2);
+        assert_eq!(rule.count, Some(4));
+        assert_eq!(
+            rule.by_day,
+            vec![(None, Weekday::Mon), (Some(-1), Weekday::Fri)]
+        );
+
+        let rule: RecurrenceRule = "DTSTART=20250115T090000Z;FREQ=MONTHLY;UNTIL=20251231"
+            .parse()
+            .unwrap();
+        assert_eq!(rule.dtstart, NaiveDate::from_ymd_opt(2025, 1, 15).unwrap());
+        assert_eq!(rule.until, NaiveDate::from_ymd_opt(2025, 12, 31));
+    }
+    #[test]
+    fn invalid() {
+        for rule in [
+            "FREQ=DAILY",
+            "DTSTART=20250101",
+            "DTSTART=20250101;FREQ=HOURLY",
+            "DTSTART=20250101;FREQ=DAILY;INTERVAL=0",
+            "DTSTART=20250101;FREQ=DAILY;BYDAY=XX",
+            "DTSTART=20250101;FREQ=DAILY;BYDAY=xéx",
+            "DTSTART=20250101;FREQ=DAILY;BYDAY=é",
+            "DTSTART=20250101;FREQ=DAILY;BYDAY=1ÜMO",
+            "DTSTART=2025010é;FREQ=DAILY",
+            "DTSTART=20250101;FREQ=DAILY;COUNT=2;UNTIL=20250105",
+            "DTSTART=20250101;FREQ=DAILY;BYHOUR=9",
+        ] {
+            assert!(
+                matches!(
+                    rule.parse::<RecurrenceRule>(),
+                    Err(ConfigError::InvalidRrule(..))
+                ),
+                "{rule} should be invalid"
+            );
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::ConfigError;
+
+/// How a message should look in outputs that support it.
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Style {
+    /// The text color.
+    pub fg: Option<Color>,
+    /// The background color.
+    pub bg: Option<Color>,
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub italic: bool,
+    #[serde(default)]
+    pub underline: bool,
+}
+
+/// A color, either one of the 16 standard terminal colors by name (`red`, `bright_blue`, ...), or
+/// a hex color (`#ff8800` or `#f80`).
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(try_from = "String", into = "String")]
+pub enum Color {
+    /// One of the 16 standard terminal colors, from `0` (black) to `15` (bright white).
+    Ansi(u8),
+    Rgb(u8, u8, u8),
+}
+
+const NAMES: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "bright_black",
+    "bright_red",
+    "bright_green",
+    "bright_yellow",
+    "bright_blue",
+    "bright_magenta",
+    "bright_cyan",
+    "bright_white",
+];
+
+/// The xterm defaults for the 16 standard colors, for outputs that only take hex colors.
+const ANSI_RGB: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+
+impl Style {
+    /// Whether any of `bold`, `italic` or `underline` are set.
+    pub fn has_attributes(&self) -> bool {
+        self.bold || self.italic || self.underline
+    }
+
+    /// Wraps `text` in ANSI escape codes for this style. Returns `text` as is if nothing is set.
+    pub fn ansi(&self, text: &str) -> String {
+        let mut codes: Vec<String> = vec![];
+        for (set, code) in [(self.bold, "1"), (self.italic, "3"), (self.underline, "4")] {
+            if set {
+                codes.push(code.to_string());
+            }
+        }
+        if let Some(fg) = self.fg {
+            codes.push(fg.ansi(false));
+        }
+        if let Some(bg) = self.bg {
+            codes.push(bg.ansi(true));
+        }
+        match codes.is_empty() {
+            true => text.to_string(),
+            false => format!("\x1b[{}m{text}\x1b[0m", codes.join(";")),
+        }
+    }
+
+    /// Escapes `text` and wraps it in a Pango `<span>` for this style. Colors are only included if
+    /// `with_colors` is set.
+    pub fn pango(&self, text: &str, with_colors: bool) -> String {
+        let text = escape_pango(text);
+        let mut attributes: Vec<String> = vec![];
+        if with_colors {
+            if let Some(fg) = self.fg {
+                attributes.push(format!("foreground=\"{}\"", fg.hex()));
+            }
+            if let Some(bg) = self.bg {
+                attributes.push(format!("background=\"{}\"", bg.hex()));
+            }
+        }
+        for (set, attribute) in [
+            (self.bold, "weight=\"bold\""),
+            (self.italic, "style=\"italic\""),
+            (self.underline, "underline=\"single\""),
+        ] {
+            if set {
+                attributes.push(attribute.to_string());
+            }
+        }
+        match attributes.is_empty() {
+            true => text,
+            false => format!("<span {}>{text}</span>", attributes.join(" ")),
+        }
+    }
+}
+
+/// Escapes the characters that Pango markup treats specially.
+pub fn escape_pango(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+impl Color {
+    /// Returns the SGR parameters to set this color as the text color, or as the background
+    /// color if `background` is set.
+    fn ansi(&self, background: bool) -> String {
+        let base = if background { 40 } else { 30 };
+        match *self {
+            Color::Ansi(idx) if idx < 8 => format!("{}", base + idx as u32),
+            Color::Ansi(idx) => format!("{}", base + 60 + (idx as u32 % 16 - 8)),
+            Color::Rgb(r, g, b) => format!("{};2;{r};{g};{b}", base + 8),
+        }
+    }
+
+    /// Returns this color as `#rrggbb`.
+    pub fn hex(&self) -> String {
+        let (r, g, b) = match *self {
+            Color::Ansi(idx) => ANSI_RGB[idx as usize % 16],
+            Color::Rgb(r, g, b) => (r, g, b),
+        };
+        format!("#{r:02x}{g:02x}{b:02x}")
+    }
+}
+
+impl FromStr for Color {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ConfigError::InvalidColor(s.to_string());
+        if let Some(hex) = s.strip_prefix('#') {
+            let digits: Vec<u8> = hex
+                .chars()
+                .map(|c| c.to_digit(16).map(|d| d as u8))
+                .collect::<Option<_>>()
+                .ok_or_else(invalid)?;
+            return match digits[..] {
+                [r, g, b] => Ok(Color::Rgb(r * 17, g * 17, b * 17)),
+                [r1, r2, g1, g2, b1, b2] => {
+                    Ok(Color::Rgb(r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2))
+                }
+                _ => Err(invalid()),
+            };
+        }
+        let name = s.to_ascii_lowercase().replace('-', "_");
+        NAMES
+            .iter()
+            .position(|&n| n == name)
+            .map(|idx| Color::Ansi(idx as u8))
+            .ok_or_else(invalid)
+    }
+}
+impl TryFrom<String> for Color {
+    type Error = ConfigError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Color::Ansi(idx) => write!(f, "{}", NAMES[*idx as usize % 16]),
+            Color::Rgb(..) => write!(f, "{}", self.hex()),
+        }
+    }
+}
+impl From<Color> for String {
+    fn from(value: Color) -> Self {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn parse_colors() {
+        assert_eq!("red".parse::<Color>().unwrap(), Color::Ansi(1));
+        assert_eq!("Bright-Blue".parse::<Color>().unwrap(), Color::Ansi(12));
+        assert_eq!(
+            "#ff8800".parse::<Color>().unwrap(),
+            Color::Rgb(0xff, 0x88, 0x00)
+        );
+        assert_eq!(
+            "#F80".parse::<Color>().unwrap(),
+            Color::Rgb(0xff, 0x88, 0x00)
+        );
+        for invalid in ["", "#", "#ff88", "#gg0000", "purple", "#ff88000"] {
+            assert!(
+                matches!(invalid.parse::<Color>(), Err(ConfigError::InvalidColor(_))),
+                "{invalid} should be invalid"
+            );
+        }
+    }
+    #[test]
+    fn color_hex() {
+        assert_eq!(Color::Ansi(9).hex(), "#ff0000");
+        assert_eq!(Color::Rgb(1, 2, 255).hex(), "#0102ff");
+        assert_eq!(Color::Ansi(12).to_string(), "bright_blue");
+        assert_eq!(Color::Rgb(1, 2, 255).to_string(), "#0102ff");
+    }
+    #[test]
+    fn style_ansi() {
+        let style = Style {
+            fg: Some(Color::Ansi(1)),
+            bg: Some(Color::Ansi(12)),
+            bold: true,
+            underline: true,
+            ..Default::default()
+        };
+        assert_eq!(style.ansi("hai"), "\x1b[1;4;31;104mhai\x1b[0m");
+        let style = Style {
+            fg: Some(Color::Rgb(255, 136, 0)),
+            italic: true,
+            ..Default::default()
+        };
+        assert_eq!(style.ansi("hai"), "\x1b[3;38;2;255;136;0mhai\x1b[0m");
+        assert_eq!(Style::default().ansi("hai"), "hai");
+    }
+    #[test]
+    fn style_pango() {
+        let style = Style {
+            fg: Some(Color::Ansi(1)),
+            bold: true,
+            italic: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            style.pango("<3 & hugs", true),
+            "<span foreground=\"#cd0000\" weight=\"bold\" style=\"italic\">&lt;3 &amp; hugs</span>"
+        );
+        assert_eq!(
+            style.pango("hai", false),
+            "<span weight=\"bold\" style=\"italic\">hai</span>"
+        );
+        assert_eq!(Style::default().pango("a&b", true), "a&amp;b");
+    }
+}
@@ -1,36 +1,446 @@
-use std::process::{Command, Output};
+use std::{
+    process::{Command, ExitStatus, Output},
+    str::FromStr,
+};
 
-use chrono::{DateTime, Datelike, FixedOffset, Local, Weekday};
-use evalexpr::{context_map, eval_boolean_with_context, DefaultNumericTypes, HashMapContext};
+use chrono::{
+    DateTime, Datelike, FixedOffset, Local, Months, NaiveDate, NaiveTime, TimeDelta, Timelike,
+    Weekday,
+};
+use evalexpr::{
+    DefaultNumericTypes, EvalexprResult, HashMapContext, context_map, eval_boolean_with_context,
+};
 
 use crate::config::{
-    CustomCommand, DayOf, MergeStrategy, RunCondition, TimeRange, TimeRangeMessage,
+    Anchor, CustomCommand, DateSpan, DayOf, HolidayMatcher, MergeStrategy, NthWeekday, Pick,
+    PoolMessage, RelativeDate, RunCondition, SpanDate, TimeOfDaySpan, TimeRange, TimeRangeMessage,
 };
+use crate::explain::Step;
+use crate::render::MessageKind;
+use crate::rrule::{Frequency, RecurrenceRule};
 
 impl TimeRange {
-    fn evaluate(&self, dt: DateTime<FixedOffset>) -> bool {
-        let match_year = match &self.year {
-            None => true,
-            Some(years) => years.iter().any(|&f| f == dt.year()),
+    /// Whether every field that is set matches at `dt`.
+    pub fn evaluate(&self, dt: DateTime<FixedOffset>) -> bool {
+        self.checks(dt, false).iter().all(|&(_, matched)| matched)
+    }
+
+    /// Returns the result of every field that is set, along with the name of the field. If
+    /// `whole_day` is set, `hour`, `minute` and `between` are left out.
+    fn checks(&self, now: DateTime<FixedOffset>, whole_day: bool) -> Vec<(&'static str, bool)> {
+        let mut checks = self.date_checks(now.date_naive());
+        if !whole_day {
+            checks.extend(self.time_of_day_checks(now.time()));
+        }
+        checks
+    }
+
+    /// Returns the result of every field that is set and does not depend on the time of day,
+    /// along with the name of the field.
+    fn date_checks(&self, date: NaiveDate) -> Vec<(&'static str, bool)> {
+        let match_day = self.day_of.as_ref().map(|day_of| match day_of {
+            DayOf::Week(weekdays) => weekdays.iter().any(|&wk| wk == date.weekday()),
+            DayOf::Month(days) => {
+                let month_len = days_in_month(date.year(), date.month()) as i32;
+                days.iter().any(|&d| {
+                    let d = if d < 0 {
+                        month_len + 1 + d as i32
+                    } else {
+                        d as i32
+                    };
+                    d == date.day() as i32
+                })
+            }
+            DayOf::Nth(nths) => nths.iter().any(|nth| nth.matches(date)),
+        });
+
+        [
+            (
+                "year",
+                self.year
+                    .as_ref()
+                    .map(|years| years.iter().any(|&f| f == date.year())),
+            ),
+            (
+                "month",
+                self.month
+                    .as_ref()
+                    .map(|months| months.iter().any(|&m| m as u32 == date.month0())),
+            ),
+            (
+                "week",
+                self.week
+                    .as_ref()
+                    .map(|weeks| weeks.iter().any(|&w| w == date.iso_week().week())),
+            ),
+            ("day_of", match_day),
+            ("span", self.span.as_ref().map(|span| span.contains(date))),
+            (
+                "relative_to",
+                self.relative_to
+                    .as_ref()
+                    .map(|relative| relative.matches(date)),
+            ),
+            (
+                "holiday",
+                self.holiday
+                    .as_ref()
+                    .map(|holiday| holiday.holiday_on(date).is_some()),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, matched)| Some((name, matched?)))
+        .collect()
+    }
+
+    /// Like `date_checks`, but for `hour`, `minute` and `between`.
+    fn time_of_day_checks(&self, time: NaiveTime) -> Vec<(&'static str, bool)> {
+        [
+            (
+                "hour",
+                self.hour
+                    .as_ref()
+                    .map(|hours| hours.iter().any(|&h| h == time.hour())),
+            ),
+            (
+                "minute",
+                self.minute
+                    .as_ref()
+                    .map(|minutes| minutes.iter().any(|&m| m == time.minute())),
+            ),
+            (
+                "between",
+                self.between.as_ref().map(|span| span.contains(time)),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, matched)| Some((name, matched?)))
+        .collect()
+    }
+}
+
+impl TimeOfDaySpan {
+    /// Checks if `time` falls within `from` (inclusive) and `to` (exclusive). If `to` is earlier
+    /// than `from`, the span wraps past midnight.
+    fn contains(&self, time: NaiveTime) -> bool {
+        if self.from <= self.to {
+            self.from <= time && time < self.to
+        } else {
+            self.from <= time || time < self.to
+        }
+    }
+}
+
+impl NthWeekday {
+    fn matches(&self, date: NaiveDate) -> bool {
+        if date.weekday() != self.weekday {
+            return false;
+        }
+        let n = if self.n < 0 {
+            -(((days_in_month(date.year(), date.month()) - date.day()) / 7 + 1) as i32)
+        } else {
+            ((date.day() - 1) / 7 + 1) as i32
         };
-        let match_month = match &self.month {
-            None => true,
-            Some(months) => months.iter().any(|&m| m as u32 == dt.month0()),
+        n == self.n as i32
+    }
+}
+
+/// Returns the number of days in `month` of `year`.
+pub(crate) fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|first| first.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
+impl RecurrenceRule {
+    /// Checks if `date` is one of the occurrences of this rule.
+    pub(crate) fn matches(&self, date: NaiveDate) -> bool {
+        if date < self.dtstart
+            || self.until.is_some_and(|until| date > until)
+            || self.exdates.contains(&date)
+        {
+            return false;
+        }
+        // without COUNT, only the period that contains `date` can have it as an occurrence.
+        if self.count.is_none() {
+            return self
+                .period_of(date)
+                .and_then(|n| self.period_start(n))
+                .is_some_and(|start| self.occurrences_in(start).contains(&date));
+        }
+        let mut seen = 0;
+        for period in 0.. {
+            let Some(start) = self.period_start(period) else {
+                return false;
+            };
+            if start > date {
+                return false;
+            }
+            for occurrence in self.occurrences_in(start) {
+                if occurrence < self.dtstart {
+                    continue;
+                }
+                if self.count.is_some_and(|count| seen >= count) || occurrence > date {
+                    return false;
+                }
+                if occurrence == date {
+                    return true;
+                }
+                seen += 1;
+            }
+        }
+        false
+    }
+
+    /// Returns `n` if `date` (on or after `dtstart`) is in the `n`th period of this rule, or `None`
+    /// if it is in one of the periods skipped by `INTERVAL`.
+    fn period_of(&self, date: NaiveDate) -> Option<u32> {
+        let days = (date - self.dtstart).num_days();
+        let units = match self.freq {
+            Frequency::Daily => days,
+            Frequency::Weekly => {
+                let back = self.dtstart.weekday().days_since(self.week_start) as i64;
+                (days + back) / 7
+            }
+            Frequency::Monthly => {
+                (date.year() - self.dtstart.year()) as i64 * 12 + date.month() as i64
+                    - self.dtstart.month() as i64
+            }
+            Frequency::Yearly => (date.year() - self.dtstart.year()) as i64,
         };
-        let match_week = match &self.week {
-            None => true,
-            Some(weeks) => weeks.iter().any(|&w| w == dt.iso_week().week()),
+        let units = u32::try_from(units).ok()?;
+        (units % self.interval == 0).then_some(units / self.interval)
+    }
+
+    /// Returns the first day of the `n`th period (day, week, month or year) of this rule.
+    fn period_start(&self, n: u32) -> Option<NaiveDate> {
+        let steps = n.checked_mul(self.interval)?;
+        match self.freq {
+            Frequency::Daily => self
+                .dtstart
+                .checked_add_signed(TimeDelta::days(steps as i64)),
+            Frequency::Weekly => {
+                let back = self.dtstart.weekday().days_since(self.week_start) as i64;
+                self.dtstart
+                    .checked_sub_signed(TimeDelta::days(back))?
+                    .checked_add_signed(TimeDelta::weeks(steps as i64))
+            }
+            Frequency::Monthly => self
+                .dtstart
+                .with_day(1)?
+                .checked_add_months(Months::new(steps)),
+            Frequency::Yearly => NaiveDate::from_ymd_opt(self.dtstart.year(), 1, 1)?
+                .checked_add_months(Months::new(steps.checked_mul(12)?)),
+        }
+    }
+
+    /// Returns every occurrence in the period starting at `start`, in order.
+    fn occurrences_in(&self, start: NaiveDate) -> Vec<NaiveDate> {
+        let end = match self.freq {
+            Frequency::Daily => start.checked_add_signed(TimeDelta::days(1)),
+            Frequency::Weekly => start.checked_add_signed(TimeDelta::weeks(1)),
+            Frequency::Monthly => start.checked_add_months(Months::new(1)),
+            Frequency::Yearly => start.checked_add_months(Months::new(12)),
+        };
+        let Some(end) = end else {
+            return vec![];
+        };
+
+        // without any BYxxx parts, the rule repeats on the same day as `dtstart`.
+        let no_days = self.by_day.is_empty() && self.by_month_day.is_empty();
+        let by_day = match self.freq {
+            Frequency::Weekly if no_days => vec![(None, self.dtstart.weekday())],
+            _ => self.by_day.clone(),
+        };
+        let by_month_day = match self.freq {
+            Frequency::Monthly | Frequency::Yearly if no_days => vec![self.dtstart.day() as i32],
+            _ => self.by_month_day.clone(),
+        };
+        let by_month = match self.freq {
+            Frequency::Yearly if no_days && self.by_month.is_empty() => {
+                vec![self.dtstart.month()]
+            }
+            _ => self.by_month.clone(),
         };
-        let match_day = match &self.day_of {
-            None => true,
-            Some(DayOf::Week(weekdays)) => weekdays.iter().any(|&wk| wk == dt.weekday()),
-            Some(DayOf::Month(days)) => days.iter().any(|&d| d as u32 == dt.day()),
+        // ordinals in BYDAY count within the month, unless this is a YEARLY rule without BYMONTH.
+        let ordinal_in_year = self.freq == Frequency::Yearly && by_month.is_empty();
+
+        let candidates: Vec<NaiveDate> = start
+            .iter_days()
+            .take_while(|&d| d < end)
+            .filter(|d| by_month.is_empty() || by_month.contains(&d.month()))
+            .filter(|d| {
+                let month_len = days_in_month(d.year(), d.month()) as i32;
+                by_month_day.is_empty()
+                    || by_month_day.iter().any(|&md| {
+                        let md = if md < 0 { month_len + 1 + md } else { md };
+                        md == d.day() as i32
+                    })
+            })
+            .filter(|d| {
+                by_day.is_empty()
+                    || by_day.iter().any(|&(ordinal, weekday)| {
+                        d.weekday() == weekday
+                            && match ordinal {
+                                None => true,
+                                Some(_)
+                                    if !matches!(
+                                        self.freq,
+                                        Frequency::Monthly | Frequency::Yearly
+                                    ) =>
+                                {
+                                    true
+                                }
+                                Some(ord) => weekday_ordinal(*d, ord < 0, ordinal_in_year) == ord,
+                            }
+                    })
+            })
+            .collect();
+
+        if self.by_set_pos.is_empty() {
+            return candidates;
+        }
+        let len = candidates.len() as i32;
+        let mut selected: Vec<NaiveDate> = self
+            .by_set_pos
+            .iter()
+            .filter_map(|&pos| {
+                let idx = if pos < 0 { len + pos } else { pos - 1 };
+                candidates.get(usize::try_from(idx).ok()?).copied()
+            })
+            .collect();
+        selected.sort();
+        selected.dedup();
+        selected
+    }
+}
+
+/// Returns which occurrence of its weekday `date` is in its month (or year). Counts from the end
+/// if `from_end` is set, as a negative number.
+fn weekday_ordinal(date: NaiveDate, from_end: bool, in_year: bool) -> i32 {
+    let (day, len) = if in_year {
+        let len = if NaiveDate::from_ymd_opt(date.year(), 2, 29).is_some() {
+            366
+        } else {
+            365
         };
+        (date.ordinal() as i32, len)
+    } else {
+        (
+            date.day() as i32,
+            days_in_month(date.year(), date.month()) as i32,
+        )
+    };
+    if from_end {
+        -((len - day) / 7 + 1)
+    } else {
+        (day - 1) / 7 + 1
+    }
+}
 
-        match_year && match_month && match_week && match_day
+impl DateSpan {
+    /// Checks if `date` falls within `from` and `to`, both inclusive. Year-less spans recur every
+    /// year, and wrap past the end of the year if `to` is earlier than `from`.
+    fn contains(&self, date: NaiveDate) -> bool {
+        match (self.from, self.to) {
+            (SpanDate::Date(from), SpanDate::Date(to)) => from <= date && date <= to,
+            (from, to) => {
+                let (from, to) = (from.month_day(), to.month_day());
+                let today = (date.month(), date.day());
+                if from <= to {
+                    from <= today && today <= to
+                } else {
+                    from <= today || today <= to
+                }
+            }
+        }
     }
 }
 
+impl SpanDate {
+    fn month_day(&self) -> (u32, u32) {
+        match *self {
+            SpanDate::Date(date) => (date.month(), date.day()),
+            SpanDate::Annual { month, day } => (month, day),
+        }
+    }
+}
+
+impl HolidayMatcher {
+    /// Returns the name of the holiday on `date`, if there is one and it is in `names`.
+    fn holiday_on(&self, date: NaiveDate) -> Option<&'static str> {
+        self.country
+            .holidays_on(date)
+            .map(|holiday| holiday.name)
+            .find(|name| match &self.names {
+                None => true,
+                Some(names) => names.iter().any(|n| n.eq_ignore_ascii_case(name)),
+            })
+    }
+}
+
+impl RelativeDate {
+    fn matches(&self, date: NaiveDate) -> bool {
+        // large offsets can push the date into the previous or next year.
+        (date.year() - 1..=date.year() + 1).any(|year| {
+            self.anchor
+                .date(year)
+                .and_then(|anchor| anchor.checked_add_signed(TimeDelta::days(self.offset_days)))
+                == Some(date)
+        })
+    }
+}
+
+impl Anchor {
+    /// Returns the date of this anchor in `year`, on the Gregorian calendar.
+    pub fn date(&self, year: i32) -> Option<NaiveDate> {
+        match self {
+            Anchor::Easter => easter(year),
+            Anchor::OrthodoxEaster => orthodox_easter(year),
+        }
+    }
+}
+
+/// Computes the date of Western (Gregorian) Easter Sunday in `year`, using the anonymous
+/// Gregorian algorithm.
+pub fn easter(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// Computes the date of Orthodox Easter Sunday in `year`, using the Meeus Julian algorithm, then
+/// converting the result to the Gregorian calendar.
+pub fn orthodox_easter(year: i32) -> Option<NaiveDate> {
+    let a = year % 4;
+    let b = year % 7;
+    let c = year % 19;
+    let d = (19 * c + 15) % 30;
+    let e = (2 * a + 4 * b - d + 34) % 7;
+    let month = (d + e + 114) / 31;
+    let day = (d + e + 114) % 31 + 1;
+    let julian_to_gregorian = year / 100 - year / 400 - 2;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)?
+        .checked_add_signed(TimeDelta::days(julian_to_gregorian as i64))
+}
+
 impl TimeRangeMessage {
     /// Evaluates the contained `TimeRange` and if it is true, return the configured message.
     /// Otherwise returns `None`.
@@ -44,10 +454,11 @@ impl TimeRangeMessage {
     /// let range = TimeRangeMessage {
     ///     message: Some("hewwo !".to_string()),
     ///     time: Some(TimeRange {
-    ///         day_of: Some(DayOf::Month(HashSet::from_iter(vec![now.day() as u8].into_iter()))),
+    ///         day_of: Some(DayOf::Month(HashSet::from_iter(vec![now.day() as i8].into_iter()))),
     ///         week: None,
     ///         month: None,
     ///         year: None,
+    ///         ..Default::default()
     ///     }),
     ///     ..Default::default()
     /// };
@@ -56,53 +467,284 @@ impl TimeRangeMessage {
     /// assert_eq!(result.unwrap(), "hewwo !");
     /// ```
     pub fn try_message(&self, week_start_day: Option<Weekday>) -> Option<String> {
+        self.try_message_at(Local::now().fixed_offset(), week_start_day)
+    }
+
+    /// Like `try_message`, but evaluates this rule at `dt` instead of right now. `dt` is also
+    /// passed on to the environment variables of `command` and `condition`.
+    pub fn try_message_at(
+        &self,
+        dt: DateTime<FixedOffset>,
+        week_start_day: Option<Weekday>,
+    ) -> Option<String> {
+        self.try_message_with_kind_at(dt, week_start_day, &mut fastrand::Rng::new())
+            .map(|(message, _)| message)
+    }
+
+    /// Like `try_message_at`, but also returns whether the message came from `message` or from
+    /// the output of `command`. A message out of `messages` is picked with `rng`.
+    pub fn try_message_with_kind_at(
+        &self,
+        dt: DateTime<FixedOffset>,
+        week_start_day: Option<Weekday>,
+        rng: &mut fastrand::Rng,
+    ) -> Option<(String, MessageKind)> {
         let week_start_day = week_start_day.unwrap_or(Weekday::Sun);
-        let now = Local::now().fixed_offset();
-        if self.evaluate(now, week_start_day) {
-            self.message(now, week_start_day)
+        if self.evaluate(dt, week_start_day, false, false) {
+            self.message(dt, week_start_day, rng)
         } else {
             None
         }
     }
 
-    fn message(&self, now: DateTime<FixedOffset>, week_start_day: Weekday) -> Option<String> {
-        self.command.as_ref().map_or_else(
-            || self.message.clone(),
-            |command| command.run(now, week_start_day).or(self.message.clone()),
-        )
+    fn message(
+        &self,
+        now: DateTime<FixedOffset>,
+        week_start_day: Weekday,
+        rng: &mut fastrand::Rng,
+    ) -> Option<(String, MessageKind)> {
+        let holiday = self.holiday_name(now);
+        let output = self
+            .command
+            .as_ref()
+            .and_then(|command| command.run(now, week_start_day, holiday));
+        match output {
+            Some(output) => Some((output, MessageKind::Command)),
+            None => self
+                .text(now, rng)
+                .map(|message| (render_template(message, now, holiday), MessageKind::Message)),
+        }
     }
 
-    fn evaluate(&self, now: DateTime<FixedOffset>, week_start_day: Weekday) -> bool {
-        match (&self.time, &self.condition) {
-            (Some(time), None) => time.evaluate(now),
-            (None, Some(condition)) => condition.evaluate(now, week_start_day),
-            (Some(time), Some(condition)) => {
-                let time_res = time.evaluate(now);
-                let cond_res = condition.evaluate(now, week_start_day);
-                self.merge_strategy.apply(time_res, cond_res)
-            }
-            _ => false,
+    /// Returns `message`, or one of `messages` picked according to `pick`.
+    fn text(&self, now: DateTime<FixedOffset>, rng: &mut fastrand::Rng) -> Option<&str> {
+        if self.messages.is_empty() {
+            return self.message.as_deref();
         }
+        let picked = match self.pick {
+            Pick::Random => self.messages.get(rng.usize(..self.messages.len())),
+            Pick::Weighted => {
+                let total: u64 = self.messages.iter().map(|m| m.weight() as u64).sum();
+                if total == 0 {
+                    return None;
+                }
+                let mut roll = rng.u64(..total);
+                self.messages.iter().find(|m| {
+                    let weight = m.weight() as u64;
+                    if roll < weight {
+                        true
+                    } else {
+                        roll -= weight;
+                        false
+                    }
+                })
+            }
+            Pick::Rotate => {
+                let day = now.date_naive().num_days_from_ce() as usize;
+                self.messages.get(day % self.messages.len())
+            }
+        };
+        picked.map(PoolMessage::message)
     }
 
-    /// similar to `try_message`, but takes a fixed DateTime. for testing.
-    #[cfg(test)]
-    fn try_with_datetime(
+    /// Like `try_message`, but checks if this rule matches at any time during `date` instead of
+    /// right now. `time.hour`, `time.minute`, `time.between` and the time fields of `cron` only
+    /// have to match together at some minute of the day, while `condition` and `command` are run
+    /// as if it was midnight.
+    pub fn try_message_on(
         &self,
-        dt: DateTime<FixedOffset>,
+        date: NaiveDate,
         week_start_day: Option<Weekday>,
     ) -> Option<String> {
         let week_start_day = week_start_day.unwrap_or(Weekday::Sun);
-        if self.evaluate(dt, week_start_day) {
-            self.message(dt, week_start_day)
+        let midnight = local_midnight(date)?;
+        if self.evaluate(midnight, week_start_day, true, false) {
+            self.message(midnight, week_start_day, &mut fastrand::Rng::new())
+                .map(|(message, _)| message)
         } else {
             None
         }
     }
+
+    /// Scans forward from `from` (inclusive) for at most `horizon` days, and returns the first
+    /// `count` days this rule matches on. Days are checked like in `try_message_on`, but `command`
+    /// is never run. If `skip_shell` is set, the `shell` part of `condition` is ignored.
+    pub fn next_dates(
+        &self,
+        from: NaiveDate,
+        horizon: u32,
+        count: usize,
+        week_start_day: Option<Weekday>,
+        skip_shell: bool,
+    ) -> Vec<NaiveDate> {
+        let week_start_day = week_start_day.unwrap_or(Weekday::Sun);
+        from.iter_days()
+            .take(horizon as usize)
+            .filter(|&date| {
+                local_midnight(date).is_some_and(|midnight| {
+                    self.evaluate(midnight, week_start_day, true, skip_shell)
+                })
+            })
+            .take(count)
+            .collect()
+    }
+
+    /// Evaluates this rule at `now`. If `whole_day` is set, the rule matches if it matches at any
+    /// minute of that day, except for `condition`, which is only evaluated at `now`. If
+    /// `skip_shell` is set, the `shell` part of `condition` is ignored.
+    fn evaluate(
+        &self,
+        now: DateTime<FixedOffset>,
+        week_start_day: Weekday,
+        whole_day: bool,
+        skip_shell: bool,
+    ) -> bool {
+        self.evaluate_with_steps(now, week_start_day, whole_day, skip_shell)
+            .0
+    }
+
+    /// Like `evaluate`, but also returns the result of every field that is set.
+    pub(crate) fn evaluate_with_steps(
+        &self,
+        now: DateTime<FixedOffset>,
+        week_start_day: Weekday,
+        whole_day: bool,
+        skip_shell: bool,
+    ) -> (bool, Vec<Step>) {
+        if !self.is_enabled() {
+            return (false, vec![Step::new(0, "enabled", false)]);
+        }
+        let holiday = self.holiday_name(now);
+        let mut steps = vec![];
+        let time_res = self.evaluate_time(now, whole_day, holiday, &mut steps);
+        let cond_res = self.condition.as_ref().and_then(|condition| {
+            let mut cond_steps = vec![];
+            let matched = condition.evaluate_with_steps(
+                now,
+                week_start_day,
+                holiday,
+                skip_shell,
+                &mut cond_steps,
+            )?;
+            steps.push(Step::new(0, "condition", matched));
+            steps.append(&mut cond_steps);
+            Some(matched)
+        });
+        let matched = match (time_res, cond_res) {
+            (Some(time_res), None) => time_res,
+            (None, Some(cond_res)) => cond_res,
+            (Some(time_res), Some(cond_res)) => {
+                let matched = self.merge_strategy.apply(time_res, cond_res);
+                steps.push(
+                    Step::new(0, "merge_strategy", matched)
+                        .with_detail(format!("{:?}", self.merge_strategy)),
+                );
+                matched
+            }
+            (None, None) => false,
+        };
+        (matched, steps)
+    }
+
+    /// Evaluates `time`, `cron` and `rrule` together, and adds their results to `steps`. All of
+    /// them have to match if more than one is set. Returns `None` if none are set.
+    fn evaluate_time(
+        &self,
+        now: DateTime<FixedOffset>,
+        whole_day: bool,
+        holiday: Option<&str>,
+        steps: &mut Vec<Step>,
+    ) -> Option<bool> {
+        let date = now.date_naive();
+        let mut results = vec![];
+        if let Some(time) = &self.time {
+            let checks = time.checks(now, whole_day);
+            let matched = checks.iter().all(|&(_, matched)| matched);
+            steps.push(Step::new(0, "time", matched));
+            for (name, matched) in checks {
+                let step = Step::new(1, name, matched);
+                steps.push(match (name, holiday) {
+                    ("holiday", Some(holiday)) => step.with_detail(holiday.to_string()),
+                    _ => step,
+                });
+            }
+            results.push(matched);
+        }
+        if let Some(cron) = &self.cron {
+            let matched = match whole_day {
+                true => cron.matches_date(date),
+                false => cron.matches(now),
+            };
+            steps.push(Step::new(0, "cron", matched).with_detail(format!("`{cron}`")));
+            results.push(matched);
+        }
+        if let Some(rrule) = &self.rrule {
+            let matched = rrule.matches(date);
+            steps.push(Step::new(0, "rrule", matched));
+            results.push(matched);
+        }
+        let date_res = results.into_iter().reduce(|a, b| a && b);
+        match date_res {
+            Some(true) if whole_day => {
+                let matched = self.matches_any_minute();
+                steps.push(Step::new(0, "time of day", matched));
+                Some(matched)
+            }
+            other => other,
+        }
+    }
+
+    /// Whether the time of day parts of `time` and `cron` all match at the same minute of some
+    /// day. Seconds are not checked.
+    fn matches_any_minute(&self) -> bool {
+        (0..24 * 60)
+            .filter_map(|minute| NaiveTime::from_num_seconds_from_midnight_opt(minute * 60, 0))
+            .any(|time| {
+                let time_res = self.time.as_ref().is_none_or(|range| {
+                    range
+                        .time_of_day_checks(time)
+                        .iter()
+                        .all(|&(_, matched)| matched)
+                });
+                time_res
+                    && self
+                        .cron
+                        .as_ref()
+                        .is_none_or(|cron| cron.matches_minute(time))
+            })
+    }
+
+    /// Whether this rule is used at all, which it is unless `enabled` is `false`.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    /// Returns a short description of this rule: its `message`, or the command it runs.
+    pub fn label(&self) -> Option<&str> {
+        self.messages
+            .first()
+            .map(PoolMessage::message)
+            .or(self.message.as_deref())
+            .or(self.command.as_ref().map(|command| command.run.as_str()))
+    }
+
+    /// Returns the name of today's holiday, if this rule matches on holidays.
+    pub(crate) fn holiday_name(&self, now: DateTime<FixedOffset>) -> Option<&'static str> {
+        self.time
+            .as_ref()?
+            .holiday
+            .as_ref()?
+            .holiday_on(now.date_naive())
+    }
 }
 
 impl CustomCommand {
-    fn prepare(&self, now: DateTime<FixedOffset>, week_start_day: Weekday) -> Command {
+    fn prepare(
+        &self,
+        now: DateTime<FixedOffset>,
+        week_start_day: Weekday,
+        holiday: Option<&str>,
+    ) -> Command {
         let CustomCommand {
             shell, shell_flags, ..
         } = self;
@@ -132,13 +774,21 @@ impl CustomCommand {
             ("WEEK", format!("{}", now.iso_week().week())),
             ("MONTH", format!("{}", now.month())),
             ("YEAR", format!("{}", now.year())),
+            ("HOUR", format!("{}", now.hour())),
+            ("MINUTE", format!("{}", now.minute())),
+            ("HOLIDAY_NAME", holiday.unwrap_or_default().to_string()),
         ]);
         cmd
     }
     /// Runs the input with the specified shell and shell_args, and returns the `stdout` of the
     /// command wrapped in `Some`, or `None` if the command fails and stdout is empty.
-    fn run(&self, now: DateTime<FixedOffset>, week_start_day: Weekday) -> Option<String> {
-        let mut cmd = self.prepare(now, week_start_day);
+    fn run(
+        &self,
+        now: DateTime<FixedOffset>,
+        week_start_day: Weekday,
+        holiday: Option<&str>,
+    ) -> Option<String> {
+        let mut cmd = self.prepare(now, week_start_day, holiday);
         cmd.arg(self.run.clone())
             .output()
             .ok()
@@ -156,63 +806,293 @@ impl CustomCommand {
                 }
             })?
     }
-    /// Runs the input and returns true if the command returns with exit code 0, else returns
-    /// false.
-    fn evaluate(&self, now: DateTime<FixedOffset>, week_start_day: Weekday) -> bool {
-        let mut cmd = self.prepare(now, week_start_day);
-        cmd.arg(self.run.clone())
-            .status()
-            .is_ok_and(|e| e.success())
+    /// Runs the input and returns its exit status.
+    fn status(
+        &self,
+        now: DateTime<FixedOffset>,
+        week_start_day: Weekday,
+        holiday: Option<&str>,
+    ) -> std::io::Result<ExitStatus> {
+        let mut cmd = self.prepare(now, week_start_day, holiday);
+        cmd.arg(self.run.clone()).status()
     }
 }
 
 impl RunCondition {
-    fn evaluate(&self, now: DateTime<FixedOffset>, week_start_day: Weekday) -> bool {
+    /// Runs `shell` and evaluates `predicate`, and combines their results with `merge_strategy`
+    /// if both are set.
+    pub fn evaluate(
+        &self,
+        now: DateTime<FixedOffset>,
+        week_start_day: Weekday,
+        holiday: Option<&str>,
+    ) -> bool {
+        self.evaluate_with_steps(now, week_start_day, holiday, false, &mut vec![])
+            .unwrap_or(false)
+    }
+
+    /// Like `evaluate`, but adds the result of `shell`, `predicate` and `merge_strategy` to
+    /// `steps`. If `skip_shell` is set, `shell` is ignored, and `None` is returned if there is no
+    /// `predicate` either.
+    pub(crate) fn evaluate_with_steps(
+        &self,
+        now: DateTime<FixedOffset>,
+        week_start_day: Weekday,
+        holiday: Option<&str>,
+        skip_shell: bool,
+        steps: &mut Vec<Step>,
+    ) -> Option<bool> {
+        let shell_res = self.shell.as_ref().filter(|_| !skip_shell).map(|command| {
+            let (matched, detail) = match command.status(now, week_start_day, holiday) {
+                Ok(status) => (status.success(), format!("`{}`: {status}", command.run)),
+                Err(e) => (false, format!("`{}` could not be run: {e}", command.run)),
+            };
+            steps.push(Step::new(1, "shell", matched).with_detail(detail));
+            matched
+        });
+        let expr_res = self.predicate.as_ref().map(|expr| {
+            let (matched, detail) = match evaluate_predicate(expr, now, week_start_day, holiday) {
+                Ok(value) => (value, format!("`{expr}` is {value}")),
+                Err(e) => (false, format!("`{expr}` failed: {e}")),
+            };
+            steps.push(Step::new(1, "predicate", matched).with_detail(detail));
+            matched
+        });
+
+        match (shell_res, expr_res) {
+            (Some(shell_res), None) => Some(shell_res),
+            (None, Some(expr_res)) => Some(expr_res),
+            (Some(shell_res), Some(expr_res)) => {
+                let matched = self.merge_strategy.apply(shell_res, expr_res);
+                steps.push(
+                    Step::new(1, "merge_strategy", matched)
+                        .with_detail(format!("{:?}", self.merge_strategy)),
+                );
+                Some(matched)
+            }
+            (None, None) if skip_shell => None,
+            (None, None) => Some(false),
+        }
+    }
+}
+
+/// Evaluates a `RunCondition` predicate.
+fn evaluate_predicate(
+    expr: &str,
+    now: DateTime<FixedOffset>,
+    week_start_day: Weekday,
+    holiday: Option<&str>,
+) -> EvalexprResult<bool> {
+    let ctx = predicate_context(now, week_start_day, holiday);
+    eval_boolean_with_context(expr, &ctx)
+}
+
+/// A part of a message template.
+enum TemplatePart<'a> {
+    Text(&'a str),
+    /// The text between `{` and `}`.
+    Placeholder(&'a str),
+}
+
+/// A placeholder in a message template, like `{weekday}` or `{days_until:12-25}`.
+enum Placeholder {
+    Weekday,
+    Day,
+    Month,
+    MonthName,
+    Year,
+    Week,
+    Hour,
+    Minute,
+    Date,
+    Holiday,
+    DaysUntil(SpanDate),
+    DaysSince(SpanDate),
+    AgeSince(NaiveDate),
+}
+
+/// Splits `template` into text and placeholders. `{{` and `}}` are a literal `{` and `}`, and a
+/// `{` without a closing `}` is kept as text.
+fn template_parts(template: &str) -> Vec<TemplatePart<'_>> {
+    let mut parts = vec![];
+    let mut rest = template;
+    while let Some(idx) = rest.find(['{', '}']) {
+        if idx > 0 {
+            parts.push(TemplatePart::Text(&rest[..idx]));
+        }
+        let brace = &rest[idx..idx + 1];
+        rest = &rest[idx + 1..];
+        if rest.starts_with(brace) {
+            parts.push(TemplatePart::Text(brace));
+            rest = &rest[1..];
+            continue;
+        }
+        match (brace, rest.find('}')) {
+            ("{", Some(end)) => {
+                parts.push(TemplatePart::Placeholder(&rest[..end]));
+                rest = &rest[end + 1..];
+            }
+            _ => parts.push(TemplatePart::Text(brace)),
+        }
+    }
+    if !rest.is_empty() {
+        parts.push(TemplatePart::Text(rest));
+    }
+    parts
+}
+
+impl FromStr for Placeholder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, arg) = match s.split_once(':') {
+            Some((name, arg)) => (name.trim(), Some(arg.trim())),
+            None => (s.trim(), None),
+        };
+        let date = |arg: Option<&str>| {
+            let arg = arg.ok_or(format!("`{name}` needs a date, like `{{{name}:12-25}}`"))?;
+            arg.parse::<SpanDate>()
+                .map_err(|_| format!("`{arg}` is not a date like `2025-12-25` or `12-25`"))
+        };
+        let placeholder = match name {
+            "weekday" => Placeholder::Weekday,
+            "day" => Placeholder::Day,
+            "month" => Placeholder::Month,
+            "month_name" => Placeholder::MonthName,
+            "year" => Placeholder::Year,
+            "week" => Placeholder::Week,
+            "hour" => Placeholder::Hour,
+            "minute" => Placeholder::Minute,
+            "date" => Placeholder::Date,
+            "holiday" => Placeholder::Holiday,
+            "days_until" => return date(arg).map(Placeholder::DaysUntil),
+            "days_since" => return date(arg).map(Placeholder::DaysSince),
+            "age_since" => {
+                return match date(arg)? {
+                    SpanDate::Date(date) => Ok(Placeholder::AgeSince(date)),
+                    SpanDate::Annual { .. } => Err(format!(
+                        "`{name}` needs a date with a year, like `{{{name}:1990-04-01}}`"
+                    )),
+                };
+            }
+            _ => return Err(format!("unknown placeholder `{{{s}}}`")),
+        };
+        match arg {
+            Some(_) => Err(format!("`{name}` does not take an argument")),
+            None => Ok(placeholder),
+        }
+    }
+}
+
+impl Placeholder {
+    fn render(&self, now: DateTime<FixedOffset>, holiday: Option<&str>) -> String {
+        let today = now.date_naive();
         match self {
-            RunCondition {
-                shell: Some(command),
-                predicate: None,
-                ..
-            } => command.evaluate(now, week_start_day),
-            RunCondition {
-                shell: None,
-                predicate: Some(expr),
-                ..
-            } => {
-                let ctx: HashMapContext<DefaultNumericTypes> = context_map! {
-                    "DAY_IN_WEEK" => int (now.weekday().days_since(week_start_day)),
-                    "DAY_OF_MONTH" => int (now.day()),
-                    "WEEK" => int (now.iso_week().week()),
-                    "MONTH" => int (now.month()),
-                    "YEAR" => int (now.year()),
-                }
-                .unwrap();
-                eval_boolean_with_context(expr, &ctx).is_ok_and(|b| b)
+            Placeholder::Weekday => now.format("%A").to_string(),
+            Placeholder::Day => now.day().to_string(),
+            Placeholder::Month => now.month().to_string(),
+            Placeholder::MonthName => now.format("%B").to_string(),
+            Placeholder::Year => now.year().to_string(),
+            Placeholder::Week => now.iso_week().week().to_string(),
+            Placeholder::Hour => now.format("%H").to_string(),
+            Placeholder::Minute => now.format("%M").to_string(),
+            Placeholder::Date => now.format("%Y-%m-%d").to_string(),
+            Placeholder::Holiday => holiday.unwrap_or_default().to_string(),
+            Placeholder::DaysUntil(date) => {
+                let target = match *date {
+                    SpanDate::Date(date) => Some(date),
+                    // the next time the day comes around, today included.
+                    SpanDate::Annual { month, day } => (today.year()..today.year() + 8)
+                        .filter_map(|year| NaiveDate::from_ymd_opt(year, month, day))
+                        .find(|&date| date >= today),
+                };
+                target.map_or(String::new(), |target| {
+                    (target - today).num_days().to_string()
+                })
             }
-            RunCondition {
-                shell: Some(command),
-                predicate: Some(expr),
-                merge_strategy,
-            } => {
-                let comm_res = command.evaluate(now, week_start_day);
-                let ctx: HashMapContext<DefaultNumericTypes> = context_map! {
-                    "DAY_IN_WEEK" => int (now.weekday().days_since(week_start_day)),
-                    "DAY_OF_MONTH" => int (now.day()),
-                    "WEEK" => int (now.iso_week().week()),
-                    "MONTH" => int (now.month()),
-                    "YEAR" => int (now.year()),
-                }
-                .unwrap();
-                let expr_res = eval_boolean_with_context(expr, &ctx).is_ok_and(|b| b);
-                merge_strategy.apply(comm_res, expr_res)
+            Placeholder::DaysSince(date) => {
+                let target = match *date {
+                    SpanDate::Date(date) => Some(date),
+                    // the last time the day came around, today included.
+                    SpanDate::Annual { month, day } => (today.year() - 7..=today.year())
+                        .rev()
+                        .filter_map(|year| NaiveDate::from_ymd_opt(year, month, day))
+                        .find(|&date| date <= today),
+                };
+                target.map_or(String::new(), |target| {
+                    (today - target).num_days().to_string()
+                })
+            }
+            Placeholder::AgeSince(date) => {
+                let before_anniversary = (today.month(), today.day()) < (date.month(), date.day());
+                (today.year() - date.year() - before_anniversary as i32).to_string()
             }
-            _ => false,
         }
     }
 }
 
+/// Fills in the placeholders of a message template. Placeholders that cannot be parsed are kept
+/// as they are.
+pub(crate) fn render_template(
+    template: &str,
+    now: DateTime<FixedOffset>,
+    holiday: Option<&str>,
+) -> String {
+    let mut out = String::with_capacity(template.len());
+    for part in template_parts(template) {
+        match part {
+            TemplatePart::Text(text) => out.push_str(text),
+            TemplatePart::Placeholder(raw) => match raw.parse::<Placeholder>() {
+                Ok(placeholder) => out.push_str(&placeholder.render(now, holiday)),
+                Err(_) => out.push_str(&format!("{{{raw}}}")),
+            },
+        }
+    }
+    out
+}
+
+/// Returns a description of every placeholder in `template` that cannot be parsed.
+pub(crate) fn template_errors(template: &str) -> Vec<String> {
+    template_parts(template)
+        .into_iter()
+        .filter_map(|part| match part {
+            TemplatePart::Placeholder(raw) => raw.parse::<Placeholder>().err(),
+            TemplatePart::Text(_) => None,
+        })
+        .collect()
+}
+
+/// Returns the start of `date` in the local timezone.
+fn local_midnight(date: NaiveDate) -> Option<DateTime<FixedOffset>> {
+    date.and_time(NaiveTime::MIN)
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|dt| dt.fixed_offset())
+}
+
+/// Builds the variables available to `RunCondition` predicates. `holiday` is only known for rules
+/// with a `time.holiday` matcher, so `HOLIDAY_NAME` is an empty string for every other rule, even
+/// on a holiday.
+fn predicate_context(
+    now: DateTime<FixedOffset>,
+    week_start_day: Weekday,
+    holiday: Option<&str>,
+) -> HashMapContext<DefaultNumericTypes> {
+    context_map! {
+        "DAY_IN_WEEK" => int (now.weekday().days_since(week_start_day)),
+        "DAY_OF_MONTH" => int (now.day()),
+        "WEEK" => int (now.iso_week().week()),
+        "MONTH" => int (now.month()),
+        "YEAR" => int (now.year()),
+        "HOUR" => int (now.hour()),
+        "MINUTE" => int (now.minute()),
+        "HOLIDAY_NAME" => holiday.unwrap_or_default().to_string(),
+    }
+    .unwrap()
+}
+
 impl MergeStrategy {
-    fn apply(&self, first: bool, second: bool) -> bool {
+    pub(crate) fn apply(&self, first: bool, second: bool) -> bool {
         match self {
             MergeStrategy::OR => first | second,
             MergeStrategy::AND => first & second,
@@ -238,15 +1118,22 @@ mod unit_tests {
             .unwrap()
             .fixed_offset()
     }
+    fn datetime(year: i32, month: u32, day: u32, hour: u32, min: u32) -> DateTime<FixedOffset> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, min, 0)
+            .unwrap()
+            .fixed_offset()
+    }
 
     #[test]
     fn eval_now() {
         let now = Local::now().fixed_offset();
         let time = TimeRange {
-            day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+            day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
             week: None,
             month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
             year: Some(hash_set! { now.year() }),
+            ..Default::default()
         };
         assert!(time.evaluate(now));
     }
@@ -258,6 +1145,7 @@ mod unit_tests {
             week: None,
             month: None,
             year: None,
+            ..Default::default()
         };
         let monday = date(2025, 4, 28);
         let friday = date(2025, 5, 2);
@@ -275,6 +1163,7 @@ mod unit_tests {
             week: None,
             month: None,
             year: None,
+            ..Default::default()
         };
         let first = date(2020, 1, 1);
         let second = date(2027, 3, 2);
@@ -286,12 +1175,95 @@ mod unit_tests {
         assert!(time.evaluate(fifth));
     }
     #[test]
+    fn eval_datetime_days_of_month_from_end() {
+        let time = TimeRange {
+            day_of: Some(DayOf::Month(hash_set! { -1, -2 })),
+            ..Default::default()
+        };
+        assert!(time.evaluate(date(2025, 1, 31)));
+        assert!(time.evaluate(date(2025, 1, 30)));
+        assert!(!time.evaluate(date(2025, 1, 29)));
+        assert!(time.evaluate(date(2024, 2, 29)));
+        assert!(time.evaluate(date(2024, 2, 28)));
+        assert!(time.evaluate(date(2025, 2, 28)));
+        assert!(time.evaluate(date(2025, 2, 27)));
+        assert!(time.evaluate(date(2025, 4, 30)));
+        assert!(!time.evaluate(date(2025, 4, 1)));
+    }
+    #[test]
+    fn eval_datetime_nth_weekday() {
+        let time = TimeRange {
+            day_of: Some(DayOf::Nth(hash_set! {
+                NthWeekday { weekday: Weekday::Tue, n: 2 },
+                NthWeekday { weekday: Weekday::Fri, n: -1 },
+            })),
+            ..Default::default()
+        };
+        // June 2025 starts on a Sunday.
+        let first_tuesday = date(2025, 6, 3);
+        let second_tuesday = date(2025, 6, 10);
+        let fourth_friday = date(2025, 6, 20);
+        let last_friday = date(2025, 6, 27);
+        let last_friday_of_feb = date(2024, 2, 23);
+        assert!(!time.evaluate(first_tuesday));
+        assert!(time.evaluate(second_tuesday));
+        assert!(!time.evaluate(fourth_friday));
+        assert!(time.evaluate(last_friday));
+        assert!(time.evaluate(last_friday_of_feb));
+    }
+    #[test]
+    fn computus() {
+        let western = [(2024, 3, 31), (2025, 4, 20), (2026, 4, 5), (2038, 4, 25)];
+        for (y, m, d) in western {
+            assert_eq!(easter(y), NaiveDate::from_ymd_opt(y, m, d));
+        }
+        let orthodox = [(2024, 5, 5), (2025, 4, 20), (2026, 4, 12), (2027, 5, 2)];
+        for (y, m, d) in orthodox {
+            assert_eq!(orthodox_easter(y), NaiveDate::from_ymd_opt(y, m, d));
+        }
+    }
+    #[test]
+    fn eval_datetime_relative_to() {
+        let time: TimeRange =
+            serde_json::from_str(r#"{"relative_to": {"anchor": "easter", "offset_days": -47}}"#)
+                .unwrap();
+        let mardi_gras_2025 = date(2025, 3, 4);
+        let mardi_gras_2026 = date(2026, 2, 17);
+        assert!(time.evaluate(mardi_gras_2025));
+        assert!(time.evaluate(mardi_gras_2026));
+        assert!(!time.evaluate(date(2026, 3, 4)));
+
+        let time = TimeRange {
+            relative_to: Some(RelativeDate {
+                anchor: Anchor::OrthodoxEaster,
+                offset_days: 0,
+            }),
+            ..Default::default()
+        };
+        assert!(time.evaluate(date(2024, 5, 5)));
+        assert!(!time.evaluate(date(2024, 3, 31)));
+    }
+    #[test]
+    fn eval_datetime_holiday() {
+        let time: TimeRange = serde_json::from_str(r#"{"holiday": {"country": "US"}}"#).unwrap();
+        assert!(time.evaluate(date(2025, 7, 4)));
+        assert!(time.evaluate(date(2025, 11, 27)));
+        assert!(!time.evaluate(date(2025, 11, 20)));
+
+        let time: TimeRange =
+            serde_json::from_str(r#"{"holiday": {"country": "gb", "names": ["christmas day"]}}"#)
+                .unwrap();
+        assert!(time.evaluate(date(2025, 12, 25)));
+        assert!(!time.evaluate(date(2025, 12, 26)));
+    }
+    #[test]
     fn eval_datetime_week() {
         let time = TimeRange {
             day_of: None,
             week: Some(hash_set! { 1, 2, 3 }),
             month: None,
             year: None,
+            ..Default::default()
         };
         let first = date(2025, 1, 1);
         let second = date(2025, 1, 8);
@@ -311,6 +1283,7 @@ mod unit_tests {
             week: None,
             month: Some(hash_set! { Month::January, Month::March, Month::September }),
             year: None,
+            ..Default::default()
         };
         let jan = date(2020, 1, 1);
         let march = date(2027, 3, 2);
@@ -328,6 +1301,7 @@ mod unit_tests {
             week: None,
             month: None,
             year: Some(hash_set! { 2022, 2023, 2025 }),
+            ..Default::default()
         };
         let year22 = date(2022, 1, 1);
         let year23 = date(2023, 3, 2);
@@ -338,6 +1312,72 @@ mod unit_tests {
         assert!(time.evaluate(year23));
         assert!(time.evaluate(year25));
     }
+    #[test]
+    fn eval_datetime_hour_minute() {
+        let time = TimeRange {
+            hour: Some(hash_set! { 9, 13 }),
+            minute: Some(hash_set! { 0, 30 }),
+            ..Default::default()
+        };
+        assert!(time.evaluate(datetime(2025, 6, 2, 9, 0)));
+        assert!(time.evaluate(datetime(2025, 6, 2, 13, 30)));
+        assert!(!time.evaluate(datetime(2025, 6, 2, 9, 15)));
+        assert!(!time.evaluate(datetime(2025, 6, 2, 10, 0)));
+    }
+    #[test]
+    fn eval_datetime_between() {
+        let time: TimeRange =
+            serde_json::from_str(r#"{"between": {"from": "09:00", "to": "17:30"}}"#).unwrap();
+        assert!(time.evaluate(datetime(2025, 6, 2, 9, 0)));
+        assert!(time.evaluate(datetime(2025, 6, 2, 17, 29)));
+        assert!(!time.evaluate(datetime(2025, 6, 2, 17, 30)));
+        assert!(!time.evaluate(datetime(2025, 6, 2, 8, 59)));
+    }
+    #[test]
+    fn eval_datetime_between_past_midnight() {
+        let time: TimeRange =
+            serde_json::from_str(r#"{"between": {"from": "22:00", "to": "02:00"}}"#).unwrap();
+        assert!(time.evaluate(datetime(2025, 6, 2, 23, 0)));
+        assert!(time.evaluate(datetime(2025, 6, 3, 0, 0)));
+        assert!(time.evaluate(datetime(2025, 6, 3, 1, 59)));
+        assert!(!time.evaluate(datetime(2025, 6, 3, 2, 0)));
+        assert!(!time.evaluate(datetime(2025, 6, 3, 12, 0)));
+    }
+    #[test]
+    fn eval_datetime_span() {
+        let time: TimeRange =
+            serde_json::from_str(r#"{"span": {"from": "2025-12-28", "to": "2026-01-03"}}"#)
+                .unwrap();
+        assert!(time.evaluate(date(2025, 12, 28)));
+        assert!(time.evaluate(date(2026, 1, 1)));
+        assert!(time.evaluate(date(2026, 1, 3)));
+        assert!(!time.evaluate(date(2026, 1, 4)));
+        assert!(!time.evaluate(date(2026, 12, 30)));
+    }
+    #[test]
+    fn eval_datetime_span_annual() {
+        let time: TimeRange =
+            serde_json::from_str(r#"{"span": {"from": "12-28", "to": "01-03"}}"#).unwrap();
+        assert!(time.evaluate(date(2025, 12, 28)));
+        assert!(time.evaluate(date(2027, 12, 31)));
+        assert!(time.evaluate(date(2030, 1, 3)));
+        assert!(!time.evaluate(date(2030, 1, 4)));
+        assert!(!time.evaluate(date(2030, 6, 15)));
+
+        let time: TimeRange =
+            serde_json::from_str(r#"{"span": {"from": "02-28", "to": "03-01"}}"#).unwrap();
+        assert!(time.evaluate(date(2024, 2, 29)));
+        assert!(!time.evaluate(date(2024, 3, 2)));
+    }
+    #[test]
+    fn span_rejects_mixed_dates() {
+        let time =
+            serde_json::from_str::<TimeRange>(r#"{"span": {"from": "2025-12-28", "to": "01-03"}}"#);
+        assert!(time.is_err());
+        let time =
+            serde_json::from_str::<TimeRange>(r#"{"span": {"from": "13-01", "to": "01-03"}}"#);
+        assert!(time.is_err());
+    }
 
     #[test]
     fn message_now() {
@@ -345,20 +1385,22 @@ mod unit_tests {
         let range = TimeRangeMessage {
             message: Some("hewwo !".to_string()),
             time: Some(TimeRange {
-                day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                 week: None,
                 month: None,
                 year: None,
+                ..Default::default()
             }),
             ..Default::default()
         };
         let range_tmrw = TimeRangeMessage {
             message: Some("hewwo !".to_string()),
             time: Some(TimeRange {
-                day_of: Some(DayOf::Month(hash_set! { now.day() as u8 + 1 })),
+                day_of: Some(DayOf::Month(hash_set! { now.day() as i8 + 1 })),
                 week: None,
                 month: None,
                 year: None,
+                ..Default::default()
             }),
             ..Default::default()
         };
@@ -376,6 +1418,7 @@ mod unit_tests {
                 week: None,
                 month: Some(hash_set! { Month::June }),
                 year: None,
+                ..Default::default()
             }),
             ..Default::default()
         };
@@ -386,20 +1429,11 @@ mod unit_tests {
         let ninth_june = date(2025, 6, 9);
         let third_july = date(2025, 7, 3);
 
-        assert!(range.try_with_datetime(first_june, None).is_none());
-        assert!(range.try_with_datetime(third_july, None).is_none());
-        assert_eq!(
-            range.try_with_datetime(third_june, None).unwrap(),
-            "hewwo !"
-        );
-        assert_eq!(
-            range.try_with_datetime(fifth_june, None).unwrap(),
-            "hewwo !"
-        );
-        assert_eq!(
-            range.try_with_datetime(ninth_june, None).unwrap(),
-            "hewwo !"
-        );
+        assert!(range.try_message_at(first_june, None).is_none());
+        assert!(range.try_message_at(third_july, None).is_none());
+        assert_eq!(range.try_message_at(third_june, None).unwrap(), "hewwo !");
+        assert_eq!(range.try_message_at(fifth_june, None).unwrap(), "hewwo !");
+        assert_eq!(range.try_message_at(ninth_june, None).unwrap(), "hewwo !");
     }
     #[test]
     fn command_with_default_shell() {
@@ -414,13 +1448,14 @@ mod unit_tests {
                 week: None,
                 month: Some(hash_set! { Month::June }),
                 year: None,
+                ..Default::default()
             }),
             ..Default::default()
         };
 
         let third_june = date(2025, 6, 3);
 
-        assert_eq!(range.try_with_datetime(third_june, None).unwrap(), "hi!");
+        assert_eq!(range.try_message_at(third_june, None).unwrap(), "hi!");
     }
     #[test]
     fn command_with_env_vars() {
@@ -435,6 +1470,7 @@ mod unit_tests {
                 week: None,
                 month: Some(hash_set! { Month::June }),
                 year: None,
+                ..Default::default()
             }),
             ..Default::default()
         };
@@ -442,7 +1478,7 @@ mod unit_tests {
         let third_june = date(2025, 6, 3);
 
         assert_eq!(
-            range.try_with_datetime(third_june, None).unwrap(),
+            range.try_message_at(third_june, None).unwrap(),
             format!(
                 "{} {} {} {} {} {}",
                 third_june.weekday(),
@@ -455,6 +1491,194 @@ mod unit_tests {
         );
     }
     #[test]
+    fn holiday_name_without_matcher() {
+        let range = TimeRangeMessage {
+            message: Some("no holiday matcher".to_string()),
+            condition: Some(RunCondition {
+                predicate: Some("HOLIDAY_NAME == \"\"".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        // Christmas, but the rule does not match holidays itself.
+        assert!(range.try_message_at(date(2025, 12, 25), None).is_some());
+    }
+    #[test]
+    fn command_with_holiday_name() {
+        let range = TimeRangeMessage {
+            command: Some(CustomCommand {
+                run: "echo \"Happy $HOLIDAY_NAME!\"".to_string(),
+                shell: None,
+                shell_flags: None,
+            }),
+            time: Some(serde_json::from_str(r#"{"holiday": {"country": "DE"}}"#).unwrap()),
+            condition: Some(RunCondition {
+                predicate: Some("HOLIDAY_NAME != \"Neujahr\"".to_string()),
+                ..Default::default()
+            }),
+            merge_strategy: MergeStrategy::AND,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            range.try_message_at(date(2025, 10, 3), None).unwrap(),
+            "Happy Tag der Deutschen Einheit!"
+        );
+        assert!(range.try_message_at(date(2025, 1, 1), None).is_none());
+        assert!(range.try_message_at(date(2025, 1, 2), None).is_none());
+    }
+    #[test]
+    fn eval_cron() {
+        let range: TimeRangeMessage = serde_json::from_str(
+            r#"{"message": "standup", "cron": "*/5 9 * * 1-5", "time": {"month": ["June"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            range
+                .try_message_at(datetime(2025, 6, 2, 9, 5), None)
+                .unwrap(),
+            "standup"
+        );
+        assert!(
+            range
+                .try_message_at(datetime(2025, 6, 2, 9, 6), None)
+                .is_none()
+        );
+        assert!(
+            range
+                .try_message_at(datetime(2025, 7, 1, 9, 5), None)
+                .is_none()
+        );
+
+        let range = TimeRangeMessage {
+            message: Some("hewwo".to_string()),
+            cron: Some("0 9 * * *".parse().unwrap()),
+            condition: Some(RunCondition {
+                predicate: Some("DAY_OF_MONTH == 3".to_string()),
+                ..Default::default()
+            }),
+            merge_strategy: MergeStrategy::OR,
+            ..Default::default()
+        };
+        assert!(
+            range
+                .try_message_at(datetime(2025, 6, 2, 9, 0), None)
+                .is_some()
+        );
+        assert!(
+            range
+                .try_message_at(datetime(2025, 6, 3, 12, 0), None)
+                .is_some()
+        );
+        assert!(
+            range
+                .try_message_at(datetime(2025, 6, 4, 12, 0), None)
+                .is_none()
+        );
+    }
+    #[test]
+    fn eval_rrule_weekly() {
+        let rule: RecurrenceRule = "DTSTART:20250106\nRRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH"
+            .parse()
+            .unwrap();
+        assert!(rule.matches(NaiveDate::from_ymd_opt(2025, 1, 6).unwrap()));
+        assert!(rule.matches(NaiveDate::from_ymd_opt(2025, 1, 9).unwrap()));
+        assert!(!rule.matches(NaiveDate::from_ymd_opt(2025, 1, 13).unwrap()));
+        assert!(rule.matches(NaiveDate::from_ymd_opt(2025, 1, 20).unwrap()));
+        assert!(rule.matches(NaiveDate::from_ymd_opt(2026, 1, 5).unwrap()));
+        assert!(!rule.matches(NaiveDate::from_ymd_opt(2024, 12, 23).unwrap()));
+    }
+    #[test]
+    fn eval_rrule_monthly() {
+        let every_third_month: RecurrenceRule =
+            "DTSTART=20250115;FREQ=MONTHLY;INTERVAL=3".parse().unwrap();
+        assert!(every_third_month.matches(NaiveDate::from_ymd_opt(2025, 1, 15).unwrap()));
+        assert!(!every_third_month.matches(NaiveDate::from_ymd_opt(2025, 2, 15).unwrap()));
+        assert!(every_third_month.matches(NaiveDate::from_ymd_opt(2025, 4, 15).unwrap()));
+        assert!(every_third_month.matches(NaiveDate::from_ymd_opt(2026, 1, 15).unwrap()));
+
+        // the last weekday of the month.
+        let last_workday: RecurrenceRule =
+            "DTSTART=20250101;FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1"
+                .parse()
+                .unwrap();
+        assert!(last_workday.matches(NaiveDate::from_ymd_opt(2025, 5, 30).unwrap()));
+        assert!(!last_workday.matches(NaiveDate::from_ymd_opt(2025, 5, 31).unwrap()));
+        assert!(last_workday.matches(NaiveDate::from_ymd_opt(2025, 6, 30).unwrap()));
+
+        let second_tuesday: RecurrenceRule =
+            "DTSTART=20250101;FREQ=MONTHLY;BYDAY=2TU".parse().unwrap();
+        assert!(second_tuesday.matches(NaiveDate::from_ymd_opt(2025, 6, 10).unwrap()));
+        assert!(!second_tuesday.matches(NaiveDate::from_ymd_opt(2025, 6, 3).unwrap()));
+    }
+    #[test]
+    fn eval_rrule_bounds() {
+        let counted: RecurrenceRule = "DTSTART=20250101;FREQ=DAILY;INTERVAL=2;COUNT=3"
+            .parse()
+            .unwrap();
+        assert!(counted.matches(NaiveDate::from_ymd_opt(2025, 1, 5).unwrap()));
+        assert!(!counted.matches(NaiveDate::from_ymd_opt(2025, 1, 7).unwrap()));
+
+        let until: RecurrenceRule = "DTSTART=20250101;FREQ=YEARLY;UNTIL=20270101"
+            .parse()
+            .unwrap();
+        assert!(until.matches(NaiveDate::from_ymd_opt(2027, 1, 1).unwrap()));
+        assert!(!until.matches(NaiveDate::from_ymd_opt(2028, 1, 1).unwrap()));
+
+        let excluded: RecurrenceRule = "DTSTART:20250101\nRRULE:FREQ=DAILY\nEXDATE:20250102"
+            .parse()
+            .unwrap();
+        assert!(excluded.matches(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()));
+        assert!(!excluded.matches(NaiveDate::from_ymd_opt(2025, 1, 2).unwrap()));
+        assert!(excluded.matches(NaiveDate::from_ymd_opt(2025, 1, 3).unwrap()));
+    }
+    #[test]
+    fn eval_rrule_without_count() {
+        // a COUNT that is never reached checks every period from DTSTART instead.
+        for rule in [
+            "DTSTART=20250106;FREQ=DAILY;INTERVAL=3",
+            "DTSTART=20250108;FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;WKST=SU",
+            "DTSTART=20250131;FREQ=MONTHLY;INTERVAL=2",
+            "DTSTART=20250101;FREQ=MONTHLY;BYDAY=-1FR,1MO",
+            "DTSTART=20240229;FREQ=YEARLY",
+            "DTSTART=20250101;FREQ=YEARLY;INTERVAL=2;BYMONTH=3;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=1,-1",
+        ] {
+            let fast: RecurrenceRule = rule.parse().unwrap();
+            let walked: RecurrenceRule = format!("{rule};COUNT=100000").parse().unwrap();
+            for date in NaiveDate::from_ymd_opt(2024, 12, 1)
+                .unwrap()
+                .iter_days()
+                .take(365 * 2)
+            {
+                assert_eq!(fast.matches(date), walked.matches(date), "{rule} on {date}");
+            }
+        }
+
+        let daily: RecurrenceRule = "DTSTART=19000101;FREQ=DAILY".parse().unwrap();
+        assert!(daily.matches(NaiveDate::from_ymd_opt(9999, 12, 31).unwrap()));
+    }
+    #[test]
+    fn command_with_time_env_vars() {
+        let range = TimeRangeMessage {
+            command: Some(CustomCommand {
+                run: "echo $HOUR:$MINUTE".to_string(),
+                shell: None,
+                shell_flags: None,
+            }),
+            condition: Some(RunCondition {
+                predicate: Some("HOUR == 9 && MINUTE == 5".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let morning = datetime(2025, 6, 3, 9, 5);
+        let evening = datetime(2025, 6, 3, 21, 5);
+
+        assert_eq!(range.try_message_at(morning, None).unwrap(), "9:5");
+        assert!(range.try_message_at(evening, None).is_none());
+    }
+    #[test]
     fn command_with_custom_week_start() {
         let range = TimeRangeMessage {
             command: Some(CustomCommand {
@@ -467,6 +1691,7 @@ mod unit_tests {
                 week: None,
                 month: Some(hash_set! { Month::June }),
                 year: None,
+                ..Default::default()
             }),
             ..Default::default()
         };
@@ -475,7 +1700,7 @@ mod unit_tests {
 
         assert_eq!(
             range
-                .try_with_datetime(third_june, Some(Weekday::Tue))
+                .try_message_at(third_june, Some(Weekday::Tue))
                 .unwrap(),
             format!(
                 "{} {} {} {} {} {}",
@@ -502,6 +1727,7 @@ mod unit_tests {
                 week: None,
                 month: Some(hash_set! { Month::June }),
                 year: None,
+                ..Default::default()
             }),
             ..Default::default()
         };
@@ -517,17 +1743,18 @@ mod unit_tests {
                 week: None,
                 month: Some(hash_set! { Month::June }),
                 year: None,
+                ..Default::default()
             }),
             ..Default::default()
         };
         let third_june = date(2025, 6, 3);
 
         assert_eq!(
-            with_spaces.try_with_datetime(third_june, None).unwrap(),
+            with_spaces.try_message_at(third_june, None).unwrap(),
             "hi!    "
         );
         assert_eq!(
-            with_no_newline.try_with_datetime(third_june, None).unwrap(),
+            with_no_newline.try_message_at(third_june, None).unwrap(),
             "hi! this will not have a newline"
         );
     }
@@ -544,13 +1771,14 @@ mod unit_tests {
                 week: None,
                 month: Some(hash_set! { Month::June }),
                 year: None,
+                ..Default::default()
             }),
             ..Default::default()
         };
 
         let third_june = date(2025, 6, 3);
 
-        assert_eq!(range.try_with_datetime(third_june, None).unwrap(), "hi!");
+        assert_eq!(range.try_message_at(third_june, None).unwrap(), "hi!");
     }
     #[test]
     fn command_overtakes_message() {
@@ -566,6 +1794,7 @@ mod unit_tests {
                 week: None,
                 month: Some(hash_set! { Month::June }),
                 year: None,
+                ..Default::default()
             }),
             ..Default::default()
         };
@@ -573,7 +1802,7 @@ mod unit_tests {
         let third_june = date(2025, 6, 3);
 
         assert_eq!(
-            range.try_with_datetime(third_june, None).unwrap(),
+            range.try_message_at(third_june, None).unwrap(),
             "this will get printed"
         );
     }
@@ -591,6 +1820,7 @@ mod unit_tests {
                 week: None,
                 month: Some(hash_set! { Month::June }),
                 year: None,
+                ..Default::default()
             }),
             ..Default::default()
         };
@@ -598,7 +1828,7 @@ mod unit_tests {
         let third_june = date(2025, 6, 3);
 
         assert_eq!(
-            range.try_with_datetime(third_june, None).unwrap(),
+            range.try_message_at(third_june, None).unwrap(),
             "it will fall back to this"
         );
     }
@@ -617,16 +1847,14 @@ mod unit_tests {
                 week: None,
                 month: Some(hash_set! { Month::June }),
                 year: None,
+                ..Default::default()
             }),
             ..Default::default()
         };
 
         let third_june = date(2025, 6, 3);
 
-        assert_eq!(
-            range.try_with_datetime(third_june, None).unwrap(),
-            "existing"
-        );
+        assert_eq!(range.try_message_at(third_june, None).unwrap(), "existing");
         std::fs::remove_file("existing").unwrap();
     }
     #[test]
@@ -649,11 +1877,12 @@ mod unit_tests {
                 week: None,
                 month: Some(hash_set! { Month::June }),
                 year: None,
+                ..Default::default()
             }),
             ..Default::default()
         };
         assert_eq!(
-            range.try_with_datetime(third_june, None).unwrap(),
+            range.try_message_at(third_june, None).unwrap(),
             "hello world!"
         );
     }
@@ -667,13 +1896,14 @@ mod unit_tests {
                 week: None,
                 month: Some(hash_set! { Month::June }),
                 year: None,
+                ..Default::default()
             }),
             ..Default::default()
         };
 
         let third_june = date(2025, 6, 3);
 
-        assert!(range.try_with_datetime(third_june, None).is_none());
+        assert!(range.try_message_at(third_june, None).is_none());
     }
 
     #[test]
@@ -695,8 +1925,8 @@ mod unit_tests {
             ..Default::default()
         };
 
-        assert!(cond_shell_true.evaluate(now, week_start_day));
-        assert!(!cond_shell_false.evaluate(now, week_start_day));
+        assert!(cond_shell_true.evaluate(now, week_start_day, None));
+        assert!(!cond_shell_false.evaluate(now, week_start_day, None));
     }
     #[test]
     fn run_condition_predicate() {
@@ -711,8 +1941,8 @@ mod unit_tests {
             ..Default::default()
         };
 
-        assert!(cond_pred_true.evaluate(now, week_start_day));
-        assert!(!cond_pred_false.evaluate(now, week_start_day));
+        assert!(cond_pred_true.evaluate(now, week_start_day, None));
+        assert!(!cond_pred_false.evaluate(now, week_start_day, None));
     }
     #[test]
     fn run_condition_mixed() {
@@ -804,11 +2034,11 @@ mod unit_tests {
         ];
 
         let trues = cond_shell_true
-            .map(|cond| cond.evaluate(now, week_start_day))
+            .map(|cond| cond.evaluate(now, week_start_day, None))
             .into_iter()
             .reduce(|acc, b| acc | b);
         let falses = cond_shell_false
-            .map(|cond| cond.evaluate(now, week_start_day))
+            .map(|cond| cond.evaluate(now, week_start_day, None))
             .into_iter()
             .reduce(|acc, b| acc | b);
         assert!(trues.is_some_and(|b| b));
@@ -832,7 +2062,7 @@ mod unit_tests {
             ..Default::default()
         };
 
-        assert!(predicate.evaluate(now, week_start_day));
+        assert!(predicate.evaluate(now, week_start_day, None));
     }
     #[test]
     fn run_condition_none() {
@@ -843,7 +2073,7 @@ mod unit_tests {
             ..Default::default()
         };
 
-        assert!(!predicate.evaluate(now, week_start_day));
+        assert!(!predicate.evaluate(now, week_start_day, None));
     }
 
     #[test]
@@ -853,7 +2083,7 @@ mod unit_tests {
             ..Default::default()
         };
         let date = date(2025, 5, 3);
-        assert!(range.try_with_datetime(date, None).is_none())
+        assert!(range.try_message_at(date, None).is_none())
     }
     #[test]
     fn eval_run_condition() {
@@ -873,7 +2103,173 @@ mod unit_tests {
                     fastrand::u32(1..=28),
                 )
             })
-            .for_each(|date| assert_eq!(range.try_with_datetime(date, None).unwrap(), "hewwo !"));
+            .for_each(|date| assert_eq!(range.try_message_at(date, None).unwrap(), "hewwo !"));
+    }
+    #[test]
+    fn message_on_whole_day() {
+        let range = TimeRangeMessage {
+            message: Some("standup".to_string()),
+            time: Some(TimeRange {
+                day_of: Some(DayOf::Week(hash_set! { Weekday::Mon })),
+                between: Some(TimeOfDaySpan {
+                    from: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                    to: NaiveTime::from_hms_opt(9, 15, 0).unwrap(),
+                }),
+                ..Default::default()
+            }),
+            cron: Some("0 12 * * MON".parse().unwrap()),
+            ..Default::default()
+        };
+        // 2025-06-02 is a Monday.
+        let monday = NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+        // `between` and `cron` never match at the same time.
+        assert_eq!(range.try_message_on(monday, None), None);
+
+        let range = TimeRangeMessage {
+            cron: Some("5 9 * * MON".parse().unwrap()),
+            ..range
+        };
+        assert_eq!(
+            range.try_message_on(monday, None).as_deref(),
+            Some("standup")
+        );
+        assert_eq!(range.try_message_on(monday.succ_opt().unwrap(), None), None);
+        assert_eq!(range.try_message_at(date(2025, 6, 2), None), None);
+    }
+    #[test]
+    fn next_dates() {
+        let range = TimeRangeMessage {
+            message: Some("birthday".to_string()),
+            time: Some(TimeRange {
+                day_of: Some(DayOf::Month(hash_set! { 14 })),
+                month: Some(hash_set! { Month::March }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let from = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        assert_eq!(
+            range.next_dates(from, 1000, 2, None, false),
+            vec![
+                NaiveDate::from_ymd_opt(2026, 3, 14).unwrap(),
+                NaiveDate::from_ymd_opt(2027, 3, 14).unwrap()
+            ]
+        );
+        assert!(range.next_dates(from, 100, 2, None, false).is_empty());
+
+        let shell_only = TimeRangeMessage {
+            message: Some("shell".to_string()),
+            condition: Some(RunCondition {
+                shell: Some(CustomCommand {
+                    run: "true".to_string(),
+                    ..Default::default()
+                }),
+                predicate: Some("DAY_OF_MONTH == 1".to_string()),
+                merge_strategy: MergeStrategy::AND,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            shell_only.next_dates(from, 40, 5, None, true),
+            vec![from, NaiveDate::from_ymd_opt(2025, 7, 1).unwrap()]
+        );
+    }
+    #[test]
+    fn template() {
+        let now = datetime(2025, 12, 22, 9, 5);
+        assert_eq!(
+            render_template(
+                "{weekday} {day}/{month} ({month_name}) {year}, week {week}, {hour}:{minute}, {date}",
+                now,
+                None
+            ),
+            "Monday 22/12 (December) 2025, week 52, 09:05, 2025-12-22"
+        );
+        assert_eq!(
+            render_template(
+                "{days_until:12-25} days until christmas, {days_until:2026-01-01} until new year",
+                now,
+                None
+            ),
+            "3 days until christmas, 10 until new year"
+        );
+        assert_eq!(
+            render_template(
+                "{days_until:12-01} {days_since:12-25} {days_since:2025-12-01}",
+                now,
+                None
+            ),
+            "344 362 21"
+        );
+        assert_eq!(
+            render_template("{age_since:1990-12-23} {age_since:1990-12-22}", now, None),
+            "34 35"
+        );
+        assert_eq!(
+            render_template("happy {holiday}!", now, Some("Christmas Day")),
+            "happy Christmas Day!"
+        );
+    }
+    #[test]
+    fn template_literals() {
+        let now = datetime(2025, 12, 22, 9, 5);
+        assert_eq!(render_template("{{day}} }} {{", now, None), "{day} } {");
+        assert_eq!(render_template("{nope} {day", now, None), "{nope} {day");
+        assert_eq!(render_template("hai :3 }", now, None), "hai :3 }");
+        assert_eq!(
+            template_errors("{day:1} {} {day}"),
+            vec![
+                "`day` does not take an argument".to_string(),
+                "unknown placeholder `{}`".to_string(),
+            ]
+        );
+    }
+    #[test]
+    fn template_in_message() {
+        let range = TimeRangeMessage {
+            message: Some("{days_until:12-25} days left".to_string()),
+            time: Some(TimeRange {
+                month: Some(hash_set! { Month::December }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            range.try_message_at(date(2025, 12, 20), None).as_deref(),
+            Some("5 days left")
+        );
+    }
+    #[test]
+    fn message_pools() {
+        let mut range: TimeRangeMessage = serde_json::from_str(
+            r#"{
+                "messages": ["one", { "message": "two", "weight": 0 }, "three on {weekday}"],
+                "pick": "rotate",
+                "time": { "month": ["June"] }
+            }"#,
+        )
+        .unwrap();
+
+        // rotating is stable for the whole day, and moves on to the next message every day.
+        let mut seen = vec![];
+        for day in 1..=3 {
+            let morning = range.try_message_at(datetime(2025, 6, day, 8, 0), None);
+            let evening = range.try_message_at(datetime(2025, 6, day, 20, 0), None);
+            assert_eq!(morning, evening);
+            seen.push(morning.unwrap());
+        }
+        seen.sort();
+        assert_eq!(seen, vec!["one", "three on Sunday", "two"]);
+
+        range.pick = Pick::Weighted;
+        for _ in 0..50 {
+            let picked = range.try_message_at(date(2025, 6, 1), None).unwrap();
+            assert_ne!(picked, "two");
+        }
+
+        range.pick = Pick::Random;
+        let picked = range.try_message_at(date(2025, 6, 2), None).unwrap();
+        assert!(["one", "two", "three on Monday"].contains(&picked.as_str()));
     }
     #[test]
     fn eval_mixed_condition() {
@@ -884,6 +2280,7 @@ mod unit_tests {
                     week: None,
                 month: Some(hash_set! { Month::May, Month::June }),
                 year: Some(hash_set! { 2011, 2012, 2013, 2014 }),
+                ..Default::default()
             }),
             condition: Some(RunCondition {
                 predicate: Some(
@@ -902,6 +2299,6 @@ mod unit_tests {
                     fastrand::u32(1..=3),
                 )
             })
-            .for_each(|date| assert_eq!(range.try_with_datetime(date, None).unwrap(), "hewwo !"));
+            .for_each(|date| assert_eq!(range.try_message_at(date, None).unwrap(), "hewwo !"));
     }
 }
@@ -1,3 +1,4 @@
+use chrono::{DateTime, FixedOffset, Local, TimeZone};
 use occasion::{
     config::{CONFIG_FILE_NAME, CONFIG_VAR, Config},
     errors::ConfigError,
@@ -32,9 +33,9 @@ pub fn save_config(config: Config) -> Result<(), ConfigError> {
     Ok(())
 }
 
-//pub fn date(year: i32, month: u32, day: u32) -> DateTime<FixedOffset> {
-//    Local
-//        .with_ymd_and_hms(year, month, day, 0, 0, 0)
-//        .unwrap()
-//        .fixed_offset()
-//}
+pub fn datetime(year: i32, month: u32, day: u32, hour: u32, min: u32) -> DateTime<FixedOffset> {
+    Local
+        .with_ymd_and_hms(year, month, day, hour, min, 0)
+        .unwrap()
+        .fixed_offset()
+}
@@ -2,8 +2,9 @@ use chrono::{Datelike, Local};
 use chrono::{Month, Weekday};
 use map_macro::hash_set;
 use occasion::config::{
-    Config, CustomCommand, DayOf, MultipleBehavior, TimeRange, TimeRangeMessage,
+    Config, CustomCommand, DayOf, MultipleBehavior, Seed, TimeRange, TimeRangeMessage,
 };
+use occasion::render::MessageKind;
 
 mod common;
 
@@ -25,20 +26,22 @@ fn integration_with_config_multiple() {
                 TimeRangeMessage {
                     message: Some("hai".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
                 TimeRangeMessage {
                     message: Some("hewwo :3".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
@@ -54,6 +57,7 @@ fn integration_with_config_multiple() {
         assert_eq!(res, "haihewwo :3");
     });
 }
+
 #[test]
 fn integration_with_config_multiple_default_behavior() {
     common::with_config_var(|| {
@@ -63,20 +67,22 @@ fn integration_with_config_multiple_default_behavior() {
                 TimeRangeMessage {
                     message: Some("hai".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
                 TimeRangeMessage {
                     message: Some("hewwo :3".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
@@ -100,10 +106,11 @@ fn integration_with_config_single() {
             dates: vec![TimeRangeMessage {
                 message: Some("hai".to_string()),
                 time: Some(TimeRange {
-                    day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                    day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                     week: None,
                     month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                     year: Some(hash_set! { now.year() }),
+                    ..Default::default()
                 }),
                 ..Default::default()
             }],
@@ -127,10 +134,11 @@ fn integration_with_config_emoji() {
             dates: vec![TimeRangeMessage {
                 message: Some("🐈".to_string()),
                 time: Some(TimeRange {
-                    day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                    day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                     week: None,
                     month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                     year: Some(hash_set! { now.year() }),
+                    ..Default::default()
                 }),
                 ..Default::default()
             }],
@@ -145,6 +153,7 @@ fn integration_with_config_emoji() {
         assert_eq!(res, "🐈");
     });
 }
+
 #[test]
 fn integration_with_matching_and_nonmatching() {
     common::with_config_var(|| {
@@ -154,20 +163,22 @@ fn integration_with_matching_and_nonmatching() {
                 TimeRangeMessage {
                     message: Some("hai".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
                 TimeRangeMessage {
                     message: Some("hewwo :3".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 + 1 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 + 1 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
@@ -183,6 +194,7 @@ fn integration_with_matching_and_nonmatching() {
         assert_eq!(res, "hai");
     });
 }
+
 #[test]
 fn integration_with_all_custom_seperator() {
     common::with_config_var(|| {
@@ -192,30 +204,33 @@ fn integration_with_all_custom_seperator() {
                 TimeRangeMessage {
                     message: Some("hai".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
                 TimeRangeMessage {
                     message: Some("hewwo :3".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
                 TimeRangeMessage {
                     message: Some("yipee !! \n this is on a new line".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
@@ -233,6 +248,7 @@ fn integration_with_all_custom_seperator() {
         assert_eq!(res, "hai\nhewwo :3\nyipee !! \n this is on a new line");
     });
 }
+
 #[test]
 fn integration_with_first() {
     common::with_config_var(|| {
@@ -242,30 +258,33 @@ fn integration_with_first() {
                 TimeRangeMessage {
                     message: Some("hai".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
                 TimeRangeMessage {
                     message: Some("hewwo :3".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
                 TimeRangeMessage {
                     message: Some("yipee !! \n this is on a new line".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
@@ -281,6 +300,7 @@ fn integration_with_first() {
         assert_eq!(res, "hai");
     });
 }
+
 #[test]
 fn integration_with_last() {
     common::with_config_var(|| {
@@ -290,30 +310,33 @@ fn integration_with_last() {
                 TimeRangeMessage {
                     message: Some("hai".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
                 TimeRangeMessage {
                     message: Some("hewwo :3".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
                 TimeRangeMessage {
                     message: Some("yipee !! \n this is on a new line".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
@@ -329,6 +352,7 @@ fn integration_with_last() {
         assert_eq!(res, "yipee !! \n this is on a new line");
     });
 }
+
 #[test]
 fn integration_with_random() {
     common::with_config_var(|| {
@@ -338,30 +362,33 @@ fn integration_with_random() {
                 TimeRangeMessage {
                     message: Some("hai".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
                 TimeRangeMessage {
                     message: Some("hewwo :3".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
                 TimeRangeMessage {
                     message: Some("mraow".to_string()),
                     time: Some(TimeRange {
-                        day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                        day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                         week: None,
                         month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                         year: Some(hash_set! { now.year() }),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
@@ -379,6 +406,7 @@ fn integration_with_random() {
         }
     });
 }
+
 #[test]
 fn integration_with_shell_commands_with_vars() {
     common::with_config_var(|| {
@@ -390,10 +418,11 @@ fn integration_with_shell_commands_with_vars() {
                     ..Default::default()
                 }),
                 time: Some(TimeRange {
-                    day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                    day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                     week: None,
                     month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                     year: Some(hash_set! { now.year() }),
+                    ..Default::default()
                 }),
                 ..Default::default()
             }],
@@ -415,6 +444,7 @@ fn integration_with_shell_commands_with_vars() {
         );
     });
 }
+
 #[test]
 fn integration_with_shell_commands_with_vars_and_custom_week_start() {
     common::with_config_var(|| {
@@ -426,10 +456,11 @@ fn integration_with_shell_commands_with_vars_and_custom_week_start() {
                     ..Default::default()
                 }),
                 time: Some(TimeRange {
-                    day_of: Some(DayOf::Month(hash_set! { now.day() as u8 })),
+                    day_of: Some(DayOf::Month(hash_set! { now.day() as i8 })),
                     week: None,
                     month: Some(hash_set! { Month::try_from(now.month() as u8).unwrap() }),
                     year: Some(hash_set! { now.year() }),
+                    ..Default::default()
                 }),
                 ..Default::default()
             }],
@@ -452,3 +483,204 @@ fn integration_with_shell_commands_with_vars_and_custom_week_start() {
         );
     });
 }
+
+#[test]
+fn integration_output_at() {
+    common::with_config_var(|| {
+        let test_config = Config {
+            dates: vec![
+                TimeRangeMessage {
+                    command: Some(CustomCommand {
+                        run: "echo \"$DAY_OF_MONTH/$MONTH $HOUR:$MINUTE\"".to_string(),
+                        ..Default::default()
+                    }),
+                    time: Some(TimeRange {
+                        day_of: Some(DayOf::Month(hash_set! { 24 })),
+                        month: Some(hash_set! { Month::December }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                TimeRangeMessage {
+                    message: Some("never".to_string()),
+                    time: Some(TimeRange {
+                        month: Some(hash_set! { Month::January }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        common::save_config(test_config).unwrap();
+
+        let config = Config::load_or_default(false).unwrap();
+
+        let res = occasion::output_at(&config, common::datetime(2025, 12, 24, 9, 5));
+        assert_eq!(res, "24/12 9:5");
+        let res = occasion::output_at(&config, common::datetime(2025, 12, 25, 9, 5));
+        assert!(res.is_empty());
+    });
+}
+
+#[test]
+fn integration_occasions_at() {
+    common::with_config_var(|| {
+        let test_config = Config {
+            dates: vec![
+                TimeRangeMessage {
+                    message: Some("fallback".to_string()),
+                    command: Some(CustomCommand {
+                        run: "echo \"from a command\"".to_string(),
+                        ..Default::default()
+                    }),
+                    time: Some(TimeRange {
+                        month: Some(hash_set! { Month::December }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                TimeRangeMessage {
+                    message: Some("hai".to_string()),
+                    time: Some(TimeRange {
+                        day_of: Some(DayOf::Month(hash_set! { 24 })),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ],
+            multiple_behavior: Some(MultipleBehavior::Last),
+            ..Default::default()
+        };
+        common::save_config(test_config).unwrap();
+
+        let mut config = Config::load_or_default(false).unwrap();
+
+        let occasions = occasion::occasions_at(&config, common::datetime(2025, 12, 24, 9, 0));
+        assert_eq!(occasions.len(), 1);
+        assert_eq!(occasions[0].index, 1);
+        assert_eq!(occasions[0].message, "hai");
+        assert_eq!(occasions[0].kind, MessageKind::Message);
+        assert!(occasions[0].source.is_some());
+
+        config.multiple_behavior = Some(MultipleBehavior::First);
+        let occasions = occasion::occasions_at(&config, common::datetime(2025, 12, 24, 9, 0));
+        assert_eq!(occasions[0].message, "from a command");
+        assert_eq!(occasions[0].kind, MessageKind::Command);
+
+        config.multiple_behavior = Some(MultipleBehavior::Random);
+        let occasions = occasion::occasions_at(&config, common::datetime(2025, 6, 1, 9, 0));
+        assert!(occasions.is_empty());
+    });
+}
+
+#[test]
+fn integration_seeded_random() {
+    let config: Config = serde_json::from_str(
+        r#"{
+            "dates": [
+                { "messages": ["a", "b", "c", "d", "e", "f", "g", "h"], "time": { "month": ["March"] } },
+                { "message": "i", "time": { "month": ["March"] } },
+                { "message": "j", "time": { "month": ["March"] } }
+            ],
+            "multiple_behavior": "random",
+            "seed": "per_day"
+        }"#,
+    )
+    .unwrap();
+    let messages_at = |config: &Config, hour: u32, min: u32| -> Vec<String> {
+        (1..=20)
+            .map(|day| occasion::output_at(config, common::datetime(2025, 3, day, hour, min)))
+            .collect()
+    };
+
+    let morning = messages_at(&config, 8, 0);
+    assert_eq!(morning, messages_at(&config, 20, 59));
+    assert!(morning.iter().any(|message| message != &morning[0]));
+
+    let mut config = config;
+    config.seed = Some(Seed::Fixed(42));
+    let fixed = messages_at(&config, 8, 0);
+    assert!(fixed.iter().all(|message| message == &fixed[0]));
+}
+
+#[test]
+fn integration_priority() {
+    let mut config: Config = serde_json::from_str(
+        r#"{
+            "dates": [
+                { "message": "water the plants", "time": { "day_of": { "week": ["Wed"] } } },
+                { "message": "standup", "time": { "day_of": { "week": ["Wed"] } }, "priority": 1 },
+                { "message": "Christmas", "time": { "day_of": { "month": [24, 25] }, "month": ["December"] }, "priority": 10 },
+                { "message": "never", "time": { "month": ["January"] }, "priority": 100 },
+                { "message": "unimportant", "time": { "month": ["December"] }, "priority": -5 }
+            ],
+            "multiple_behavior": "highest"
+        }"#,
+    )
+    .unwrap();
+    let christmas_eve = common::datetime(2025, 12, 24, 9, 0);
+    let other_wednesday = common::datetime(2025, 12, 17, 9, 0);
+    assert_eq!(occasion::output_at(&config, christmas_eve), "Christmas");
+    assert_eq!(occasion::output_at(&config, other_wednesday), "standup");
+
+    config.multiple_behavior = Some(MultipleBehavior::TopN {
+        n: 3,
+        seperator: ", ".to_string(),
+    });
+    assert_eq!(
+        occasion::output_at(&config, christmas_eve),
+        "Christmas, standup, water the plants"
+    );
+    assert_eq!(
+        occasion::output_at(&config, common::datetime(2025, 12, 25, 9, 0)),
+        "Christmas, unimportant"
+    );
+
+    config.multiple_behavior = Some(MultipleBehavior::WeightedRandom);
+    let mut counts = std::collections::HashMap::new();
+    for _ in 0..600 {
+        *counts
+            .entry(occasion::output_at(&config, christmas_eve))
+            .or_insert(0) += 1;
+    }
+    assert!(!counts.contains_key("unimportant"));
+    assert!(counts["Christmas"] > counts["standup"]);
+    assert!(counts["standup"] > counts["water the plants"]);
+    assert_eq!(
+        occasion::output_at(&config, common::datetime(2025, 12, 1, 9, 0)),
+        "unimportant"
+    );
+}
+
+#[test]
+fn integration_groups_and_suppression() {
+    let config: Config = serde_json::from_str(
+        r#"{
+            "dates": [
+                { "id": "standup", "message": "standup at 10", "time": { "day_of": { "week": ["Mon"] } } },
+                { "message": "Christmas", "time": { "day_of": { "month": [25] }, "month": ["December"] }, "suppresses": ["standup"] },
+                { "message": "cold outside", "time": { "month": ["December"] }, "group": "weather" },
+                { "message": "snowing", "time": { "day_of": { "month": [22, 25] }, "month": ["December"] }, "group": "weather", "priority": 1 },
+                { "message": "also snowing", "time": { "day_of": { "month": [22] }, "month": ["December"] }, "group": "weather", "priority": 1 }
+            ],
+            "multiple_behavior": { "all": { "seperator": ", " } }
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        occasion::output_at(&config, common::datetime(2025, 12, 15, 9, 0)),
+        "standup at 10, cold outside"
+    );
+    let occasions = occasion::occasions_at(&config, common::datetime(2025, 12, 15, 9, 0));
+    assert_eq!(occasions[0].id.as_deref(), Some("standup"));
+    assert_eq!(occasions[1].id, None);
+    assert_eq!(
+        occasion::output_at(&config, common::datetime(2025, 12, 22, 9, 0)),
+        "standup at 10, snowing"
+    );
+    assert_eq!(
+        occasion::output_at(&config, common::datetime(2028, 12, 25, 9, 0)),
+        "Christmas, snowing"
+    );
+}